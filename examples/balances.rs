@@ -1,13 +1,21 @@
+use kraken_api::asset::AssetFamily;
 use kraken_api::auth::KrakenAuth;
 use kraken_api::client::KrakenClient;
 
 #[tokio::main]
 async fn main() {
-    let auth = KrakenAuth::api_keys("<api-key>", "<secret>");
+    let auth = KrakenAuth::from_env().unwrap();
 
     let client = KrakenClient::new(auth).unwrap();
 
     let balance = client.balance().await.unwrap();
 
     println!("Balance: {balance} BTC");
+
+    let balance = client
+        .asset_balance(&AssetFamily::new("ETH"))
+        .await
+        .unwrap();
+
+    println!("Balance: {balance} ETH");
 }
@@ -0,0 +1,25 @@
+use kraken_api::auth::KrakenAuth;
+use kraken_api::client::KrakenClient;
+use kraken_api::ws::message::{ChannelMessage, Subscription};
+use kraken_api::ws::{KrakenWsClient, WsNotification};
+
+#[tokio::main]
+async fn main() {
+    let auth = KrakenAuth::from_env().unwrap();
+
+    let client = KrakenClient::new(auth).unwrap();
+
+    let ws = KrakenWsClient::authenticated(client).await.unwrap();
+
+    let mut notifications = ws.notifications();
+
+    ws.subscribe(Subscription::Executions).await.unwrap();
+
+    while let Ok(notification) = notifications.recv().await {
+        if let WsNotification::Message(ChannelMessage::Executions { data, .. }) = notification {
+            for execution in data {
+                println!("{}: {:?}", execution.order_id, execution.exec_type);
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use kraken_api::ws::message::{ChannelMessage, Subscription};
+use kraken_api::ws::{KrakenWsClient, WsNotification};
+
+#[tokio::main]
+async fn main() {
+    let client = KrakenWsClient::new().unwrap();
+
+    let mut notifications = client.notifications();
+
+    client
+        .subscribe(Subscription::ticker(["BTC/USD"]))
+        .await
+        .unwrap();
+
+    while let Ok(notification) = notifications.recv().await {
+        if let WsNotification::Message(ChannelMessage::Ticker { data, .. }) = notification {
+            for ticker in data {
+                println!("{}: {}", ticker.symbol, ticker.last);
+            }
+        }
+    }
+}
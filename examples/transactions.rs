@@ -3,7 +3,7 @@ use kraken_api::client::KrakenClient;
 
 #[tokio::main]
 async fn main() {
-    let auth = KrakenAuth::api_keys("<api-key>", "<secret>");
+    let auth = KrakenAuth::from_env().unwrap();
 
     let client = KrakenClient::new(auth).unwrap();
 
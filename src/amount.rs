@@ -0,0 +1,311 @@
+//! Amount
+//!
+//! Kraken returns amounts as strings: they are kept as is, and converted on demand to [`f64`]
+//! or, with the `decimal` feature, to exact decimals.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::error::Error;
+
+/// Amount (balances, fees, prices, volumes, ...)
+///
+/// Keeps the original string (i.e., `0.0000500000`), serialized back as is.
+/// The equality compares the strings: compare the converted values to ignore the scale.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Amount(String);
+
+impl Amount {
+    /// Original string
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to [`f64`] (the precision may be lost)
+    #[inline]
+    pub fn to_f64(&self) -> f64 {
+        // Validated on construction
+        self.0.parse().unwrap_or_default()
+    }
+
+    /// Convert to an exact decimal, preserving the original scale
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal(&self) -> Result<Decimal, rust_decimal::Error> {
+        Decimal::from_str(&self.0).or_else(|_| Decimal::from_scientific(&self.0))
+    }
+
+    /// Check if the amount is zero
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.to_f64() == 0.0
+    }
+
+    /// Format a finite float, without exponent
+    fn from_f64(value: f64) -> Self {
+        Self(value.to_string())
+    }
+
+    /// Apply the operation exactly with the `decimal` feature (if in range), as [`f64`] otherwise
+    fn apply(
+        &self,
+        rhs: &Self,
+        #[cfg(feature = "decimal")] exact: fn(Decimal, Decimal) -> Option<Decimal>,
+        float: fn(f64, f64) -> f64,
+    ) -> Self {
+        #[cfg(feature = "decimal")]
+        if let (Ok(lhs), Ok(rhs)) = (self.to_decimal(), rhs.to_decimal()) {
+            if let Some(value) = exact(lhs, rhs) {
+                return Self::from(value);
+            }
+        }
+
+        Self::from_f64(float(self.to_f64(), rhs.to_f64()))
+    }
+}
+
+impl Default for Amount {
+    fn default() -> Self {
+        Self(String::from("0"))
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Self(s.to_string())),
+            _ => Err(Error::InvalidAmount(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<f64> for Amount {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if value.is_finite() {
+            Ok(Self::from_f64(value))
+        } else {
+            Err(Error::InvalidAmount(value.to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<Decimal> for Amount {
+    fn from(value: Decimal) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl Add for &Amount {
+    type Output = Amount;
+
+    fn add(self, rhs: Self) -> Amount {
+        self.apply(
+            rhs,
+            #[cfg(feature = "decimal")]
+            Decimal::checked_add,
+            |lhs, rhs| lhs + rhs,
+        )
+    }
+}
+
+impl Sub for &Amount {
+    type Output = Amount;
+
+    fn sub(self, rhs: Self) -> Amount {
+        self.apply(
+            rhs,
+            #[cfg(feature = "decimal")]
+            Decimal::checked_sub,
+            |lhs, rhs| lhs - rhs,
+        )
+    }
+}
+
+impl<'a> Sum<&'a Amount> for Amount {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = &'a Amount>,
+    {
+        iter.fold(Self::default(), |sum, amount| &sum + amount)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        StringOrNumber::deserialize(deserializer)?
+            .parse()?
+            .ok_or_else(|| de::Error::custom("empty amount"))
+    }
+}
+
+/// Amount returned either as string or as number (i.e., WebSocket v2)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+impl StringOrNumber {
+    fn parse<E>(self) -> Result<Option<Amount>, E>
+    where
+        E: de::Error,
+    {
+        match self {
+            Self::String(s) if s.is_empty() => Ok(None),
+            Self::String(s) => s.parse().map(Some).map_err(E::custom),
+            Self::Number(n) => match n.as_f64() {
+                // Without arbitrary precision, the float is the shortest round-trip representation
+                Some(value) if n.is_f64() => Amount::try_from(value).map(Some).map_err(E::custom),
+                _ => n.to_string().parse().map(Some).map_err(E::custom),
+            },
+        }
+    }
+}
+
+/// Deserialize an optional amount, returned either as string or as number (empty string if missing)
+pub(crate) fn deserialize_optional_amount<'de, D>(
+    deserializer: D,
+) -> Result<Option<Amount>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<StringOrNumber>::deserialize(deserializer)? {
+        Some(value) => value.parse(),
+        None => Ok(None),
+    }
+}
+
+/// Serialize an amount as number, as expected by WebSocket v2
+#[cfg(feature = "websocket")]
+pub(crate) fn serialize_amount_as_number<S>(
+    amount: &Amount,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f64(amount.to_f64())
+}
+
+#[cfg(feature = "websocket")]
+pub(crate) fn serialize_optional_amount_as_number<S>(
+    amount: &Option<Amount>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match amount {
+        Some(amount) => serialize_amount_as_number(amount, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn amount(s: &str) -> Amount {
+        s.parse().unwrap()
+    }
+
+    pub(crate) fn assert_amount(value: &Amount, expected: &str) {
+        let expected: Amount = amount(expected);
+
+        #[cfg(feature = "decimal")]
+        assert_eq!(value.to_decimal().unwrap(), expected.to_decimal().unwrap());
+
+        #[cfg(not(feature = "decimal"))]
+        assert!(
+            (value.to_f64() - expected.to_f64()).abs() < 0.0001,
+            "{value} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        let amount: Amount = "0.0000500000".parse().unwrap();
+        assert_eq!(amount.as_str(), "0.0000500000");
+        assert_eq!(amount.to_f64(), 0.00005);
+
+        assert!("invalid_number".parse::<Amount>().is_err());
+        assert!("inf".parse::<Amount>().is_err());
+        assert!("".parse::<Amount>().is_err());
+        assert!(Amount::try_from(f64::NAN).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn test_parse_amount_lossless() {
+        let amount: Amount = "0.0000500000".parse().unwrap();
+        assert_eq!(amount.to_decimal().unwrap().to_string(), "0.0000500000");
+
+        let amount: Amount = "1.5e-7".parse().unwrap();
+        assert_eq!(amount.to_decimal().unwrap().to_string(), "0.00000015");
+    }
+
+    #[test]
+    fn test_amount_serde() {
+        let amount: Amount = serde_json::from_str(r#""0.0000500000""#).unwrap();
+        assert_eq!(serde_json::to_string(&amount).unwrap(), r#""0.0000500000""#);
+
+        // Numbers are formatted without exponent
+        let amount: Amount = serde_json::from_str("0.00001").unwrap();
+        assert_eq!(amount.as_str(), "0.00001");
+        let amount: Amount = serde_json::from_str("45283").unwrap();
+        assert_eq!(amount.as_str(), "45283");
+
+        assert!(serde_json::from_str::<Amount>(r#""""#).is_err());
+        assert!(serde_json::from_str::<Amount>(r#""1,5""#).is_err());
+
+        let amount: Option<Amount> =
+            deserialize_optional_amount(&mut serde_json::Deserializer::from_str(r#""""#)).unwrap();
+        assert_eq!(amount, None);
+    }
+
+    #[test]
+    fn test_amount_arithmetic() {
+        assert_amount(&(&amount("1.5") + &amount("0.25")), "1.75");
+        assert_amount(&(&amount("1.5") - &amount("2")), "-0.5");
+
+        let amounts: Vec<Amount> = vec![amount("0.1"), amount("0.2"), amount("3.5")];
+        assert_amount(&amounts.iter().sum(), "3.8");
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn test_amount_arithmetic_exact() {
+        let sum: Amount = &amount("0.1") + &amount("0.20");
+        assert_eq!(sum.as_str(), "0.30");
+    }
+}
@@ -0,0 +1,144 @@
+//! Asset families
+//!
+//! Kraken reports the same asset under multiple tickers: the legacy name (i.e., `XXBT`), the alternate name
+//! (i.e., `XBT`) and the variants for the staked and the opt-in rewards balances (i.e., `XBT.B`, `XBT.F`).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::amount::Amount;
+use crate::constant::{TICKERS, XBT_TICKER};
+use crate::registry::AssetRegistry;
+
+/// Suffixes of the asset variants (bonded, staked, flex, ...)
+const VARIANT_SUFFIXES: &[&str] = &["B", "M", "F", "T", "S", "P"];
+
+/// Asset family: an asset and all its variants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetFamily {
+    /// Ticker for the funding endpoints (i.e., `XBT`)
+    ticker: String,
+    /// All the tickers of the family
+    tickers: HashSet<String>,
+}
+
+impl AssetFamily {
+    /// Construct the family of the Kraken alternate name (i.e., `XBT`, `ETH`, `USDC`)
+    ///
+    /// Includes the legacy names (i.e., `XXBT`, `ZUSD`) and the variants (i.e., `XBT.B`, `XBT.F`).
+    /// Use [`AssetRegistry::family`] to get the tickers listed by Kraken instead.
+    pub fn new<S>(ticker: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let ticker: String = ticker.into().to_ascii_uppercase();
+
+        let mut tickers: HashSet<String> = HashSet::from([ticker.clone()]);
+
+        // Legacy names: 3 letters, prefixed with `X` (crypto) or `Z` (fiat)
+        if ticker.len() == 3 {
+            tickers.insert(format!("X{ticker}"));
+            tickers.insert(format!("Z{ticker}"));
+        }
+
+        for suffix in VARIANT_SUFFIXES.iter() {
+            tickers.insert(format!("{ticker}.{suffix}"));
+        }
+
+        Self { ticker, tickers }
+    }
+
+    /// Bitcoin family (see [`TICKERS`])
+    pub fn bitcoin() -> Self {
+        let mut family: Self = Self::new(XBT_TICKER);
+        family
+            .tickers
+            .extend(TICKERS.iter().map(|ticker| ticker.to_string()));
+        family
+    }
+
+    /// Construct a family with the given tickers
+    pub(crate) fn with_tickers(ticker: String, tickers: HashSet<String>) -> Self {
+        Self { ticker, tickers }
+    }
+
+    /// Get the ticker for the funding endpoints (i.e., `XBT`)
+    #[inline]
+    pub fn ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    /// Get all the tickers of the family
+    #[inline]
+    pub fn tickers(&self) -> impl Iterator<Item = &str> {
+        self.tickers.iter().map(String::as_str)
+    }
+
+    /// Check if the ticker belongs to the family
+    #[inline]
+    pub fn contains(&self, ticker: &str) -> bool {
+        self.tickers.contains(ticker)
+    }
+
+    /// Sum the balances of the family
+    pub fn sum(&self, balances: &HashMap<String, Amount>) -> Amount {
+        balances
+            .iter()
+            .filter(|(ticker, _)| self.contains(ticker))
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// Check if the pair (i.e., `XXBTZUSD`) involves the family, as base or quote asset
+    ///
+    /// Pairs unknown to the registry (i.e., delisted) are matched by prefix and suffix.
+    pub fn matches_pair(&self, registry: &AssetRegistry, pair: &str) -> bool {
+        match registry.resolve_pair(pair) {
+            Some((base, quote)) => self.contains(base) || self.contains(quote),
+            None => self
+                .tickers()
+                .any(|ticker| pair.starts_with(ticker) || pair.ends_with(ticker)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::tests::{amount, assert_amount};
+
+    #[test]
+    fn test_bitcoin_family() {
+        let family = AssetFamily::bitcoin();
+
+        assert_eq!(family.ticker(), "XBT");
+        for ticker in TICKERS.iter() {
+            assert!(family.contains(ticker));
+        }
+        assert!(!family.contains("ETH"));
+        assert!(!family.contains("WBTC"));
+
+        // Without registry
+        let registry = AssetRegistry::default();
+        assert!(family.matches_pair(&registry, "XXBTZUSD"));
+        assert!(family.matches_pair(&registry, "XETHXXBT"));
+        assert!(!family.matches_pair(&registry, "XETHZUSD"));
+    }
+
+    #[test]
+    fn test_family_sum() {
+        let balances: HashMap<String, Amount> = HashMap::from([
+            (String::from("XXBT"), amount("1.5")),
+            (String::from("XBT.F"), amount("2.3")),
+            (String::from("XETH"), amount("10.0")),
+            (String::from("ETH.B"), amount("0.5")),
+            (String::from("USDC"), amount("100")),
+            (String::from("ZUSD"), amount("1000.50")),
+        ]);
+
+        assert_amount(&AssetFamily::bitcoin().sum(&balances), "3.8");
+        assert_amount(&AssetFamily::new("ETH").sum(&balances), "10.5");
+        assert_amount(&AssetFamily::new("usdc").sum(&balances), "100");
+        assert_amount(&AssetFamily::new("USD").sum(&balances), "1000.50");
+        assert_amount(&AssetFamily::new("SOL").sum(&balances), "0");
+    }
+}
@@ -1,27 +1,124 @@
 //! Kraken authentication
 
-use std::borrow::Cow;
 use std::fmt;
+use std::sync::Arc;
 use std::time::SystemTime;
 
-use base64::Engine;
-use base64::engine::general_purpose::STANDARD;
 use hmac::{Hmac, Mac};
-use sha2::{Digest, Sha256, Sha512};
-use url::Url;
+use sha1::Sha1;
+use zeroize::Zeroizing;
 
 use crate::error::Error;
 use crate::request::KrakenRequestBody;
+use crate::signer::{HmacSigner, Signer};
 
-type HmacSha512 = Hmac<Sha512>;
+type HmacSha1 = Hmac<Sha1>;
+
+/// TOTP time step, in seconds
+const TOTP_PERIOD: u64 = 30;
+/// TOTP code digits
+const TOTP_DIGITS: u32 = 6;
 
 /// Credentials needed to use private Kraken APIs.
-#[derive(Clone, PartialEq, Eq, Hash)]
+///
+/// The secrets are never printed, and are wiped from memory when dropped.
+#[derive(Clone)]
 pub struct KrakenApiCredentials {
     /// The name of the API key
     pub key: String,
-    /// The API key secret
-    pub secret: String,
+    /// The signer of the requests, holding the API key secret
+    pub signer: Arc<dyn Signer>,
+    /// Two-factor password, if enabled for the API key
+    pub otp: Option<Otp>,
+}
+
+impl fmt::Debug for KrakenApiCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KrakenApiCredentials")
+            .field("key", &self.key)
+            .field("signer", &self.signer)
+            .field("otp", &self.otp)
+            .finish()
+    }
+}
+
+impl KrakenApiCredentials {
+    /// Construct new credentials, signing the requests in-process with the secret
+    ///
+    /// Returns an error if the secret isn't valid base64.
+    pub fn new<K, S>(key: K, secret: S) -> Result<Self, Error>
+    where
+        K: Into<String>,
+        S: Into<String>,
+    {
+        Ok(Self::with_signer(key, Arc::new(HmacSigner::new(secret)?)))
+    }
+
+    /// Construct new credentials, signing the requests with a custom signer
+    pub fn with_signer<K>(key: K, signer: Arc<dyn Signer>) -> Self
+    where
+        K: Into<String>,
+    {
+        Self {
+            key: key.into(),
+            signer,
+            otp: None,
+        }
+    }
+
+    /// Set the two-factor password
+    #[inline]
+    pub fn otp(mut self, otp: Otp) -> Self {
+        self.otp = Some(otp);
+        self
+    }
+}
+
+/// Two-factor password of the API key
+#[derive(Clone, PartialEq, Eq)]
+pub enum Otp {
+    /// Static password
+    Static(Zeroizing<String>),
+    /// TOTP secret (decoded): the code is generated at every request
+    Totp(Zeroizing<Vec<u8>>),
+}
+
+impl fmt::Debug for Otp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(..) => f.debug_tuple("Static").finish(),
+            Self::Totp(..) => f.debug_tuple("Totp").finish(),
+        }
+    }
+}
+
+impl Otp {
+    /// Static password
+    pub fn password<S>(password: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Static(Zeroizing::new(password.into()))
+    }
+
+    /// TOTP (RFC 6238, SHA-1, 6 digits, 30 secs), from the base32 secret shown when setting up the 2FA
+    pub fn totp(secret: &str) -> Result<Self, Error> {
+        Ok(Self::Totp(Zeroizing::new(decode_base32(secret)?)))
+    }
+
+    /// Get the password for a request
+    pub(crate) fn generate(&self) -> Zeroizing<String> {
+        match self {
+            Self::Static(password) => password.clone(),
+            Self::Totp(secret) => {
+                let now: u64 = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                Zeroizing::new(totp(secret, now))
+            }
+        }
+    }
 }
 
 /// Kraken authentication
@@ -42,68 +139,150 @@ impl fmt::Debug for KrakenAuth {
 
 impl KrakenAuth {
     /// Construct API key credentials
-    pub fn api_keys<K, S>(key: K, secret: S) -> Self
+    ///
+    /// Returns an error if the secret isn't valid base64.
+    pub fn api_keys<K, S>(key: K, secret: S) -> Result<Self, Error>
     where
         K: Into<String>,
         S: Into<String>,
     {
-        Self::ApiKeys(KrakenApiCredentials {
-            key: key.into(),
-            secret: secret.into(),
-        })
+        Ok(Self::ApiKeys(KrakenApiCredentials::new(key, secret)?))
+    }
+
+    /// Construct API key credentials, with two-factor password
+    ///
+    /// Returns an error if the secret isn't valid base64.
+    pub fn api_keys_with_otp<K, S>(key: K, secret: S, otp: Otp) -> Result<Self, Error>
+    where
+        K: Into<String>,
+        S: Into<String>,
+    {
+        Ok(Self::ApiKeys(
+            KrakenApiCredentials::new(key, secret)?.otp(otp),
+        ))
+    }
+}
+
+impl From<KrakenApiCredentials> for KrakenAuth {
+    fn from(credentials: KrakenApiCredentials) -> Self {
+        Self::ApiKeys(credentials)
     }
 }
 
 /// Serialize a json payload, adding a nonce, and producing a signature using Kraken's scheme
 ///
 /// Arguments:
+/// * API path for the request (i.e., `/0/private/Balance`), regardless of the root URL
 /// * query_data for the request, with "nonce" value not yet assigned
-/// * url path for the request
 ///
 /// Returns:
 /// * post_data for the request (encoded query data, with nonce added)
 /// * signature over that post data string
-pub(crate) fn sign_api(
+pub(crate) async fn sign_api(
     credentials: &KrakenApiCredentials,
-    url: &Url,
+    url_path: &str,
     body: KrakenRequestBody<'_>,
 ) -> Result<(String, String), Error> {
-    // Get the path and query data
-    let url_path: &str = url.path();
-    let query_str: Option<&str> = url.query();
-
     // Serialize body to JSON
     let body_json: String = serde_json::to_string(&body)?;
 
-    // Concatenate query and body strings
-    let data: Cow<str> = match query_str {
-        Some(query) => Cow::Owned(format!("{query}{body_json}")),
-        None => Cow::Borrowed(&body_json),
-    };
+    let sig: String = credentials
+        .signer
+        .sign(url_path, body.nonce, &body_json)
+        .await?;
 
-    let sha2_result = {
-        let mut hasher = Sha256::default();
-        hasher.update(body.nonce.to_string());
-        hasher.update(data.as_ref());
-        hasher.finalize()
-    };
+    Ok((body_json, sig))
+}
 
-    let hmac_sha_key: Vec<u8> = STANDARD.decode(&credentials.secret)?;
+/// Generate the TOTP code for the unix time (in seconds)
+fn totp(secret: &[u8], time: u64) -> String {
+    let counter: u64 = time / TOTP_PERIOD;
 
-    let mut mac =
-        HmacSha512::new_from_slice(&hmac_sha_key).expect("Hmac should work with any key length");
-    mac.update(url_path.as_bytes());
-    mac.update(&sha2_result);
-    let mac = mac.finalize().into_bytes();
+    let mut mac = HmacSha1::new_from_slice(secret).expect("Hmac should work with any key length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
 
-    let sig: String = STANDARD.encode(mac);
+    // Dynamic truncation
+    let offset: usize = (hash[hash.len() - 1] & 0x0f) as usize;
+    let code: u32 = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
 
-    Ok((body_json, sig))
+    format!(
+        "{:0width$}",
+        code % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
 }
 
-pub(super) fn nonce() -> u64 {
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Decode a base32 (RFC 4648) string, ignoring case, spaces and padding
+fn decode_base32(input: &str) -> Result<Vec<u8>, Error> {
+    let mut output: Vec<u8> = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for c in input.chars() {
+        let value: u8 = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u8 - b'A',
+            c @ '2'..='7' => c as u8 - b'2' + 26,
+            ' ' | '-' | '=' => continue,
+            _ => return Err(Error::InvalidBase32),
+        };
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    if output.is_empty() {
+        return Err(Error::InvalidBase32);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base32() {
+        assert_eq!(
+            decode_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(),
+            b"12345678901234567890"
+        );
+        assert_eq!(decode_base32("mzxw 6ytb oi==").unwrap(), b"foobar");
+        assert!(decode_base32("GEZ1").is_err());
+        assert!(decode_base32("").is_err());
+    }
+
+    #[test]
+    fn test_credentials_debug_redacted() {
+        let secret = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+        let credentials = KrakenApiCredentials::new("my-key", secret)
+            .unwrap()
+            .otp(Otp::password("my-password"));
+
+        let debug = format!("{credentials:?}");
+        assert!(debug.contains("my-key"));
+        assert!(!debug.contains(secret));
+        assert!(!debug.contains("my-password"));
+    }
+
+    #[test]
+    fn test_totp() {
+        // RFC 6238 test vectors (SHA-1), truncated to 6 digits
+        let secret = b"12345678901234567890";
+        assert_eq!(totp(secret, 59), "287082");
+        assert_eq!(totp(secret, 1111111109), "081804");
+        assert_eq!(totp(secret, 1234567890), "005924");
+        assert_eq!(totp(secret, 20000000000), "353130");
+    }
 }
@@ -0,0 +1,141 @@
+//! Kraken client builder
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Client, Proxy};
+use url::Url;
+
+use crate::auth::KrakenAuth;
+use crate::client::KrakenClient;
+use crate::error::{Error, KrakenError};
+use crate::nonce::NonceProvider;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+
+type WarningFn = dyn Fn(&[KrakenError]) + Send + Sync;
+
+/// Handler of the warnings of the successful responses
+#[derive(Clone)]
+pub(crate) struct WarningHandler(pub(crate) Arc<WarningFn>);
+
+impl fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WarningHandler")
+    }
+}
+
+/// Kraken client builder
+#[derive(Debug, Clone, Default)]
+pub struct KrakenClientBuilder {
+    pub(crate) auth: KrakenAuth,
+    pub(crate) root_url: Option<Url>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) user_agent_suffix: Option<String>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) client: Option<Client>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    pub(crate) nonce_provider: Option<Arc<dyn NonceProvider>>,
+    pub(crate) warning_handler: Option<WarningHandler>,
+}
+
+impl KrakenClientBuilder {
+    /// Construct a new builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the authentication
+    #[inline]
+    pub fn auth(mut self, auth: KrakenAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Set a custom root URL (i.e., a mock server or a reverse proxy)
+    ///
+    /// The URL can have a path prefix: the API paths are appended to it.
+    #[inline]
+    pub fn root_url(mut self, url: Url) -> Self {
+        self.root_url = Some(url);
+        self
+    }
+
+    /// Set the requests timeout (default: 25 secs)
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Append a suffix to the user agent
+    #[inline]
+    pub fn user_agent_suffix<S>(mut self, suffix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.user_agent_suffix = Some(suffix.into());
+        self
+    }
+
+    /// Send the requests through a proxy
+    #[inline]
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Use an externally constructed HTTP client (i.e., to share the connection pool)
+    ///
+    /// The timeout, the user agent and the proxy of the builder are ignored: configure them in the client.
+    #[inline]
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Delay the private calls according to the API counter of the rate limiter
+    ///
+    /// Share the same limiter between the clients using the same API key.
+    #[inline]
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Retry the failed requests according to the policy
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Set a custom nonce provider (default: [`AtomicNonceProvider::shared`](crate::nonce::AtomicNonceProvider::shared))
+    ///
+    /// Share the same provider between the clients using the same API key.
+    #[inline]
+    pub fn nonce_provider(mut self, provider: Arc<dyn NonceProvider>) -> Self {
+        self.nonce_provider = Some(provider);
+        self
+    }
+
+    /// Handle the warnings (`W` severity entries) returned with the successful responses
+    ///
+    /// Without handler, the warnings are discarded: the responses with errors fail with [`Error::Kraken`] instead.
+    #[inline]
+    pub fn warning_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&[KrakenError]) + Send + Sync + 'static,
+    {
+        self.warning_handler = Some(WarningHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Build the client
+    #[inline]
+    pub fn build(self) -> Result<KrakenClient, Error> {
+        KrakenClient::from_builder(self)
+    }
+}
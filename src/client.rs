@@ -1,64 +1,399 @@
 //! Kraken client
 
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::Arc;
 use std::time::Duration;
 
+use futures_util::{Stream, TryStreamExt, stream};
 use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder, Response};
 use serde::de::DeserializeOwned;
+use tokio::sync::RwLock;
+use tokio::time;
 use url::Url;
 
-use crate::auth::{self, KrakenAuth};
-use crate::constant::{API_ROOT_URL, API_VERSION, USER_AGENT_NAME, XBT_TICKER};
+use crate::amount::Amount;
+use crate::asset::AssetFamily;
+use crate::auth::{self, KrakenApiCredentials, KrakenAuth, Otp};
+use crate::builder::{KrakenClientBuilder, WarningHandler};
+use crate::constant::{API_ROOT_URL, API_VERSION, USER_AGENT_NAME};
 use crate::error::Error;
+use crate::nonce::{AtomicNonceProvider, NonceProvider};
+use crate::rate_limit::RateLimiter;
+use crate::registry::AssetRegistry;
 use crate::request::{
-    DepositStatus, Empty, GetTradesHistory, KrakenRequestBody, Request, WithdrawStatus,
+    AddOrder, CancelAllOrdersAfter, CancelOrder, DepositStatus, EarnAllocate,
+    EarnAllocationsOptions, EarnStatus, EarnStrategiesOptions, EditOrder, Empty, GetAssetPairs,
+    GetAssets, GetClosedOrders, GetDepositAddresses, GetDepositMethods, GetDepth, GetLedgers,
+    GetOhlc, GetOpenOrders, GetOpenPositions, GetRecentTrades, GetSpread, GetTicker,
+    GetTradeBalance, GetTradeVolume, GetTradesHistory, GetWithdrawAddresses, GetWithdrawInfo,
+    GetWithdrawMethods, KrakenRequestBody, LedgersOptions, OhlcInterval, QueryLedgers,
+    QueryOptions, QueryOrders, Request, Withdraw, WithdrawCancel, WithdrawStatus,
 };
 use crate::response::{
-    BitcoinBalances, DepositTransaction, KrakenResult, Trade, TradesHistory, WithdrawTransaction,
+    AddOrderResult, AssetInfo, AssetPairInfo, Balances, CancelAllOrdersAfterResult,
+    CancelOrderResult, ClosedOrders, DepositAddress, DepositMethod, DepositTransaction,
+    EarnAllocations, EarnOperationStatus, EarnStrategies, EditOrderResult, ExtendedBalance,
+    FundingTransactions, KrakenResult, LedgerEntry, Ledgers, Ohlc, OpenOrders, OpenPosition,
+    OrderBook, OrderInfo, Page, RecentSpreads, RecentTrades, TickerInfo, Trade, TradeBalance,
+    TradeVolume, TradesHistory, WebSocketsToken, WithdrawAddress, WithdrawInfo, WithdrawMethod,
+    WithdrawResult, WithdrawTransaction,
 };
+use crate::retry::RetryPolicy;
+
+/// Default requests timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(25);
 
 enum Api<'a> {
+    Ticker {
+        /// Asset pairs to get data for (all pairs if empty).
+        pairs: &'a [&'a str],
+    },
+    Assets {
+        /// Assets to get info for (all assets if empty).
+        assets: &'a [&'a str],
+    },
+    AssetPairs {
+        /// Asset pairs to get info for (all pairs if empty).
+        pairs: &'a [&'a str],
+    },
+    Ohlc {
+        pair: &'a str,
+        interval: Option<OhlcInterval>,
+        since: Option<u64>,
+    },
+    Depth {
+        pair: &'a str,
+        /// Maximum number of asks/bids.
+        count: Option<u16>,
+    },
+    Trades {
+        pair: &'a str,
+        since: Option<u64>,
+        count: Option<u16>,
+    },
+    Spread {
+        pair: &'a str,
+        since: Option<u64>,
+    },
     Balance,
+    BalanceEx,
+    TradeBalance {
+        /// Base asset used to determine balance (default: `ZUSD`).
+        asset: Option<&'a str>,
+    },
+    TradeVolume {
+        /// Asset pairs to get the fee info for (none if empty).
+        pairs: &'a [&'a str],
+    },
+    OpenPositions {
+        /// Transaction IDs to restrict output to (all positions if empty).
+        txids: &'a [&'a str],
+        /// Whether to include the profit/loss calculations.
+        docalcs: bool,
+    },
     DepositStatus {
         /// Currency to get transactions for.
         asset: Option<&'a str>,
+        options: &'a QueryOptions,
     },
     WithdrawStatus {
         /// Currency to get transactions for.
         asset: Option<&'a str>,
+        options: &'a QueryOptions,
     },
     TradesHistory {
-        start: Option<u64>,
-        end: Option<u64>,
+        options: &'a QueryOptions,
+        /// Result offset for pagination.
+        ofs: Option<u64>,
+    },
+    Ledgers {
+        options: &'a LedgersOptions,
+        /// Result offset for pagination.
+        ofs: Option<u64>,
+    },
+    QueryLedgers {
+        /// Ledger IDs to query (max 20).
+        ids: &'a [&'a str],
+    },
+    AddOrder(&'a AddOrder),
+    EditOrder(&'a EditOrder),
+    CancelOrder {
+        /// Transaction ID, user reference or client order ID.
+        txid: &'a str,
+    },
+    CancelAll,
+    CancelAllOrdersAfter {
+        /// Timeout in seconds (0 to disable the timer).
+        timeout: u64,
+    },
+    OpenOrders,
+    ClosedOrders {
+        options: &'a QueryOptions,
+        /// Result offset for pagination.
+        ofs: Option<u64>,
     },
+    QueryOrders {
+        /// Transaction IDs to query (max 50).
+        txids: &'a [&'a str],
+    },
+    DepositMethods {
+        asset: &'a str,
+    },
+    DepositAddresses {
+        asset: &'a str,
+        /// Name of the deposit method.
+        method: &'a str,
+        /// Whether or not to generate a new address.
+        new: bool,
+        /// Amount to deposit (required for Lightning).
+        amount: Option<Amount>,
+    },
+    WithdrawMethods {
+        asset: &'a str,
+    },
+    WithdrawAddresses {
+        asset: &'a str,
+        /// Name of the withdrawal method.
+        method: Option<&'a str>,
+    },
+    WithdrawInfo {
+        asset: &'a str,
+        /// Withdrawal key name, as set up on the account.
+        key: &'a str,
+        amount: Amount,
+    },
+    Withdraw(&'a Withdraw),
+    WithdrawCancel {
+        asset: &'a str,
+        /// Withdrawal reference ID.
+        refid: &'a str,
+    },
+    GetWebSocketsToken,
+    EarnStrategies(&'a EarnStrategiesOptions),
+    EarnAllocate {
+        strategy_id: &'a str,
+        amount: Amount,
+    },
+    EarnDeallocate {
+        strategy_id: &'a str,
+        amount: Amount,
+    },
+    EarnAllocateStatus {
+        strategy_id: &'a str,
+    },
+    EarnDeallocateStatus {
+        strategy_id: &'a str,
+    },
+    EarnAllocations(&'a EarnAllocationsOptions),
 }
 
 impl Api<'_> {
     fn method(&self) -> &str {
         match self {
+            Self::Ticker { .. } => "Ticker",
+            Self::Assets { .. } => "Assets",
+            Self::AssetPairs { .. } => "AssetPairs",
+            Self::Ohlc { .. } => "OHLC",
+            Self::Depth { .. } => "Depth",
+            Self::Trades { .. } => "Trades",
+            Self::Spread { .. } => "Spread",
             Self::Balance => "Balance",
+            Self::BalanceEx => "BalanceEx",
+            Self::TradeBalance { .. } => "TradeBalance",
+            Self::TradeVolume { .. } => "TradeVolume",
+            Self::OpenPositions { .. } => "OpenPositions",
             Self::DepositStatus { .. } => "DepositStatus",
             Self::WithdrawStatus { .. } => "WithdrawStatus",
             Self::TradesHistory { .. } => "TradesHistory",
+            Self::Ledgers { .. } => "Ledgers",
+            Self::QueryLedgers { .. } => "QueryLedgers",
+            Self::AddOrder(..) => "AddOrder",
+            Self::EditOrder(..) => "EditOrder",
+            Self::CancelOrder { .. } => "CancelOrder",
+            Self::CancelAll => "CancelAll",
+            Self::CancelAllOrdersAfter { .. } => "CancelAllOrdersAfter",
+            Self::OpenOrders => "OpenOrders",
+            Self::ClosedOrders { .. } => "ClosedOrders",
+            Self::QueryOrders { .. } => "QueryOrders",
+            Self::DepositMethods { .. } => "DepositMethods",
+            Self::DepositAddresses { .. } => "DepositAddresses",
+            Self::WithdrawMethods { .. } => "WithdrawMethods",
+            Self::WithdrawAddresses { .. } => "WithdrawAddresses",
+            Self::WithdrawInfo { .. } => "WithdrawInfo",
+            Self::Withdraw(..) => "Withdraw",
+            Self::WithdrawCancel { .. } => "WithdrawCancel",
+            Self::GetWebSocketsToken => "GetWebSocketsToken",
+            Self::EarnStrategies(..) => "Earn/Strategies",
+            Self::EarnAllocate { .. } => "Earn/Allocate",
+            Self::EarnDeallocate { .. } => "Earn/Deallocate",
+            Self::EarnAllocateStatus { .. } => "Earn/AllocateStatus",
+            Self::EarnDeallocateStatus { .. } => "Earn/DeallocateStatus",
+            Self::EarnAllocations(..) => "Earn/Allocations",
+        }
+    }
+
+    /// Cost of the call for the API counter (public calls aren't counted).
+    ///
+    /// The order calls are limited by the trading engine, with separate counters per pair.
+    fn cost(&self) -> u8 {
+        match self {
+            Self::Ticker { .. }
+            | Self::Assets { .. }
+            | Self::AssetPairs { .. }
+            | Self::Ohlc { .. }
+            | Self::Depth { .. }
+            | Self::Trades { .. }
+            | Self::Spread { .. } => 0,
+            Self::AddOrder(..)
+            | Self::EditOrder(..)
+            | Self::CancelOrder { .. }
+            | Self::CancelAll
+            | Self::CancelAllOrdersAfter { .. } => 0,
+            Self::TradesHistory { .. } | Self::Ledgers { .. } | Self::QueryLedgers { .. } => 2,
+            _ => 1,
         }
     }
 
+    /// Whether the call can be safely repeated (i.e., after a timeout).
+    fn is_idempotent(&self) -> bool {
+        !matches!(
+            self,
+            Self::AddOrder(..)
+                | Self::EditOrder(..)
+                | Self::Withdraw(..)
+                | Self::DepositAddresses { new: true, .. }
+                | Self::EarnAllocate { .. }
+                | Self::EarnDeallocate { .. }
+        )
+    }
+
     fn body(&self) -> Request {
         match self {
-            Self::Balance => Request::Empty(Empty {}),
-            Self::DepositStatus { asset } => Request::DepositStatus(DepositStatus {
+            Self::Ticker { pairs } => Request::Ticker(GetTicker {
+                pair: (!pairs.is_empty()).then(|| pairs.join(",")),
+            }),
+            Self::Assets { assets } => Request::Assets(GetAssets {
+                asset: (!assets.is_empty()).then(|| assets.join(",")),
+            }),
+            Self::AssetPairs { pairs } => Request::AssetPairs(GetAssetPairs {
+                pair: (!pairs.is_empty()).then(|| pairs.join(",")),
+            }),
+            Self::Ohlc {
+                pair,
+                interval,
+                since,
+            } => Request::Ohlc(GetOhlc {
+                pair,
+                interval: *interval,
+                since: *since,
+            }),
+            Self::Depth { pair, count } => Request::Depth(GetDepth {
+                pair,
+                count: *count,
+            }),
+            Self::Trades { pair, since, count } => Request::Trades(GetRecentTrades {
+                pair,
+                since: *since,
+                count: *count,
+            }),
+            Self::Spread { pair, since } => Request::Spread(GetSpread {
+                pair,
+                since: *since,
+            }),
+            Self::Balance | Self::BalanceEx | Self::CancelAll | Self::GetWebSocketsToken => {
+                Request::Empty(Empty {})
+            }
+            Self::TradeBalance { asset } => {
+                Request::TradeBalance(GetTradeBalance { asset: *asset })
+            }
+            Self::TradeVolume { pairs } => Request::TradeVolume(GetTradeVolume {
+                pair: (!pairs.is_empty()).then(|| pairs.join(",")),
+            }),
+            Self::OpenPositions { txids, docalcs } => Request::OpenPositions(GetOpenPositions {
+                txid: (!txids.is_empty()).then(|| txids.join(",")),
+                docalcs: *docalcs,
+            }),
+            Self::DepositStatus { asset, options } => Request::DepositStatus(DepositStatus {
                 asset: asset.as_deref(),
+                options,
             }),
-            Self::WithdrawStatus { asset } => Request::WithdrawStatus(WithdrawStatus {
+            Self::WithdrawStatus { asset, options } => Request::WithdrawStatus(WithdrawStatus {
                 asset: asset.as_deref(),
+                options,
+            }),
+            Self::TradesHistory { options, ofs } => Request::TradesHistory(GetTradesHistory {
+                r#type: options.trade_type,
+                trades: true,
+                start: options.start.as_ref(),
+                end: options.end.as_ref(),
+                ofs: *ofs,
+            }),
+            Self::Ledgers { options, ofs } => Request::Ledgers(GetLedgers { options, ofs: *ofs }),
+            Self::QueryLedgers { ids } => Request::QueryLedgers(QueryLedgers { id: ids.join(",") }),
+            Self::AddOrder(order) => Request::AddOrder(order),
+            Self::EditOrder(order) => Request::EditOrder(order),
+            Self::CancelOrder { txid } => Request::CancelOrder(CancelOrder { txid }),
+            Self::CancelAllOrdersAfter { timeout } => {
+                Request::CancelAllOrdersAfter(CancelAllOrdersAfter { timeout: *timeout })
+            }
+            Self::OpenOrders => Request::OpenOrders(GetOpenOrders { trades: true }),
+            Self::ClosedOrders { options, ofs } => Request::ClosedOrders(GetClosedOrders {
+                trades: true,
+                start: options.start.as_ref(),
+                end: options.end.as_ref(),
+                ofs: *ofs,
             }),
-            Self::TradesHistory { start, end } => Request::TradesHistory(GetTradesHistory {
-                r#type: "closed position",
+            Self::QueryOrders { txids } => Request::QueryOrders(QueryOrders {
                 trades: true,
-                start: *start,
-                end: *end,
-                ofs: None,
+                txid: txids.join(","),
+            }),
+            Self::DepositMethods { asset } => Request::DepositMethods(GetDepositMethods { asset }),
+            Self::DepositAddresses {
+                asset,
+                method,
+                new,
+                amount,
+            } => Request::DepositAddresses(GetDepositAddresses {
+                asset,
+                method,
+                new: *new,
+                amount: amount.as_ref(),
+            }),
+            Self::WithdrawMethods { asset } => {
+                Request::WithdrawMethods(GetWithdrawMethods { asset })
+            }
+            Self::WithdrawAddresses { asset, method } => {
+                Request::WithdrawAddresses(GetWithdrawAddresses {
+                    asset,
+                    method: *method,
+                })
+            }
+            Self::WithdrawInfo { asset, key, amount } => {
+                Request::WithdrawInfo(GetWithdrawInfo { asset, key, amount })
+            }
+            Self::Withdraw(withdraw) => Request::Withdraw(withdraw),
+            Self::WithdrawCancel { asset, refid } => {
+                Request::WithdrawCancel(WithdrawCancel { asset, refid })
+            }
+            Self::EarnStrategies(options) => Request::EarnStrategies(options),
+            Self::EarnAllocate {
+                strategy_id,
+                amount,
+            }
+            | Self::EarnDeallocate {
+                strategy_id,
+                amount,
+            } => Request::EarnAllocate(EarnAllocate {
+                amount,
+                strategy_id,
             }),
+            Self::EarnAllocateStatus { strategy_id }
+            | Self::EarnDeallocateStatus { strategy_id } => {
+                Request::EarnStatus(EarnStatus { strategy_id })
+            }
+            Self::EarnAllocations(options) => Request::EarnAllocations(options),
         }
     }
 }
@@ -72,33 +407,88 @@ pub struct KrakenClient {
     client: Client,
     /// Authentication
     auth: KrakenAuth,
+    /// Rate limiter for the private calls
+    rate_limiter: Option<RateLimiter>,
+    /// Retry policy
+    retry_policy: Option<RetryPolicy>,
+    /// Nonce provider for the private calls
+    nonce: Arc<dyn NonceProvider>,
+    /// Asset registry (fetched lazily)
+    registry: Arc<RwLock<Option<Arc<AssetRegistry>>>>,
+    /// Handler of the response warnings
+    warning_handler: Option<WarningHandler>,
 }
 
 impl KrakenClient {
     /// Construct a new client.
+    #[inline]
     pub fn new(auth: KrakenAuth) -> Result<Self, Error> {
+        Self::builder().auth(auth).build()
+    }
+
+    /// Get a new client builder.
+    #[inline]
+    pub fn builder() -> KrakenClientBuilder {
+        KrakenClientBuilder::new()
+    }
+
+    pub(crate) fn from_builder(builder: KrakenClientBuilder) -> Result<Self, Error> {
+        let mut root_url: Url = match builder.root_url {
+            Some(url) => url,
+            None => Url::parse(API_ROOT_URL)?,
+        };
+
+        // The API paths are joined to the root URL: keep its path prefix, if any
+        if !root_url.path().ends_with('/') {
+            let path: String = format!("{}/", root_url.path());
+            root_url.set_path(&path);
+        }
+
+        let client: Client = match builder.client {
+            Some(client) => client,
+            None => {
+                let user_agent: String = match builder.user_agent_suffix {
+                    Some(suffix) => format!("{USER_AGENT_NAME} {suffix}"),
+                    None => USER_AGENT_NAME.to_string(),
+                };
+
+                let mut client = Client::builder()
+                    .user_agent(user_agent)
+                    .timeout(builder.timeout.unwrap_or(DEFAULT_TIMEOUT));
+
+                if let Some(proxy) = builder.proxy {
+                    client = client.proxy(proxy);
+                }
+
+                client.build()?
+            }
+        };
+
         Ok(Self {
-            root_url: Url::parse(API_ROOT_URL)?,
-            client: Client::builder()
-                .user_agent(USER_AGENT_NAME)
-                .timeout(Duration::from_secs(25))
-                .build()?,
-            auth,
+            root_url,
+            client,
+            auth: builder.auth,
+            rate_limiter: builder.rate_limiter,
+            retry_policy: builder.retry_policy,
+            nonce: builder
+                .nonce_provider
+                .unwrap_or_else(|| AtomicNonceProvider::shared()),
+            registry: Arc::new(RwLock::new(None)),
+            warning_handler: builder.warning_handler,
         })
     }
 
-    async fn query<T>(&self, url: Url, headers: HeaderMap, body_json: String) -> Result<T, Error>
+    /// Get the URL of an API path, relative to the root URL.
+    fn endpoint(&self, path: &str) -> Result<Url, Error> {
+        Ok(self.root_url.join(path.trim_start_matches('/'))?)
+    }
+
+    async fn query<T>(&self, request: RequestBuilder) -> Result<T, Error>
     where
         T: DeserializeOwned,
     {
-        // Post request
-        let response: Response = self
-            .client
-            .post(url)
-            .headers(headers)
-            .body(body_json)
-            .send()
-            .await?;
+        // Send request
+        let response: Response = request.send().await?;
 
         // If HTTP error, return error
         let response: Response = response.error_for_status()?;
@@ -107,7 +497,29 @@ impl KrakenClient {
         let result: KrakenResult<T> = response.json().await?;
 
         // Extract the result
-        result.extract()
+        let (result, warnings) = result.extract()?;
+
+        if let (Some(handler), false) = (&self.warning_handler, warnings.is_empty()) {
+            (handler.0)(&warnings);
+        }
+
+        Ok(result)
+    }
+
+    async fn query_public<T>(&self, api: Api<'_>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let method: &str = api.method();
+
+        let path: String = format!("/{API_VERSION}/public/{method}");
+        let url: Url = self.endpoint(&path)?;
+
+        // Query
+        self.retry(true, || {
+            self.query(self.client.get(url.clone()).query(&api.body()))
+        })
+        .await
     }
 
     async fn query_private<T>(&self, api: Api<'_>) -> Result<T, Error>
@@ -116,74 +528,682 @@ impl KrakenClient {
     {
         match &self.auth {
             KrakenAuth::ApiKeys(creds) => {
-                let method: &str = api.method();
+                self.retry(api.is_idempotent(), || self.send_private(creds, &api))
+                    .await
+            }
+            KrakenAuth::None => Err(Error::MissingCredentials),
+        }
+    }
+
+    /// Sign and send a private request, with a new nonce.
+    async fn send_private<T>(&self, creds: &KrakenApiCredentials, api: &Api<'_>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        // Wait before generating the nonce, to keep them ordered
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(api.cost()).await;
+        }
+
+        let method: &str = api.method();
+
+        let path: String = format!("/{API_VERSION}/private/{method}");
+        let url: Url = self.endpoint(&path)?;
 
-                let path: String = format!("/{API_VERSION}/private/{method}");
-                let url: Url = self.root_url.join(&path)?;
+        // Construct body data
+        let body: KrakenRequestBody = KrakenRequestBody {
+            nonce: self.nonce.next_nonce().await?,
+            otp: creds.otp.as_ref().map(Otp::generate),
+            request: api.body(),
+        };
 
-                // Construct body data
-                let body: KrakenRequestBody = KrakenRequestBody {
-                    nonce: auth::nonce(),
-                    request: api.body(),
+        // Sign the request
+        let (body_json, sig) = auth::sign_api(creds, &path, body).await?;
+
+        // Build headers
+        let mut headers: HeaderMap = HeaderMap::with_capacity(2);
+        headers.insert("API-Key", HeaderValue::from_str(&creds.key)?);
+        headers.insert("API-Sign", HeaderValue::from_str(&sig)?);
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        // Query
+        let result: Result<T, Error> = self
+            .query(self.client.post(url).headers(headers).body(body_json))
+            .await;
+
+        // The counter is out of sync
+        if let (Some(limiter), Err(e)) = (&self.rate_limiter, &result) {
+            if e.is_rate_limited() {
+                limiter.saturate();
+            }
+        }
+
+        result
+    }
+
+    /// Run the request, retrying it according to the retry policy (if any).
+    async fn retry<T, F, Fut>(&self, idempotent: bool, request: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempt: u32 = 1;
+
+        loop {
+            match (&self.retry_policy, request().await) {
+                (Some(policy), Err(e)) if policy.should_retry(&e, attempt, idempotent) => {
+                    time::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                (_, result) => return result,
+            }
+        }
+    }
+
+    /// Stream the pages of an `ofs` paginated API, until all the `count` entries are fetched.
+    fn paginate<'a, T, F>(&'a self, api: F) -> impl Stream<Item = Result<T, Error>> + 'a
+    where
+        T: DeserializeOwned + Page + 'a,
+        F: Fn(u64) -> Api<'a> + 'a,
+    {
+        stream::try_unfold(Some(0), move |ofs: Option<u64>| {
+            let next: Option<(u64, Api<'a>)> = ofs.map(|ofs| (ofs, api(ofs)));
+
+            async move {
+                // No more pages
+                let Some((ofs, api)) = next else {
+                    return Ok(None);
                 };
 
-                // Sign the request
-                let (body_json, sig) = auth::sign_api(creds, &url, body)?;
+                let page: T = self.query_private(api).await?;
 
-                // Build headers
-                let mut headers: HeaderMap = HeaderMap::with_capacity(2);
-                headers.insert("API-Key", HeaderValue::from_str(&creds.key)?);
-                headers.insert("API-Sign", HeaderValue::from_str(&sig)?);
-                headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+                // Compute the offset of the next page, if any
+                let len: u64 = page.len() as u64;
+                let next_ofs: u64 = ofs + len;
+                let next: Option<u64> = (len > 0 && next_ofs < page.count()).then_some(next_ofs);
 
-                // Query
-                self.query(url, headers, body_json).await
+                Ok(Some((page, next)))
             }
-            KrakenAuth::None => Err(Error::MissingCredentials),
+        })
+    }
+
+    /// Get information about the given assets (all assets if empty).
+    pub async fn assets(&self, assets: &[&str]) -> Result<HashMap<String, AssetInfo>, Error> {
+        self.query_public(Api::Assets { assets }).await
+    }
+
+    /// Get information about the given asset pairs (all pairs if empty).
+    pub async fn asset_pairs(
+        &self,
+        pairs: &[&str],
+    ) -> Result<HashMap<String, AssetPairInfo>, Error> {
+        self.query_public(Api::AssetPairs { pairs }).await
+    }
+
+    /// Get the asset registry, fetching it at the first call.
+    pub async fn asset_registry(&self) -> Result<Arc<AssetRegistry>, Error> {
+        if let Some(registry) = self.registry.read().await.as_ref() {
+            return Ok(registry.clone());
         }
+
+        self.refresh_asset_registry().await
+    }
+
+    /// Fetch the asset registry again (i.e., to get the newly listed assets).
+    pub async fn refresh_asset_registry(&self) -> Result<Arc<AssetRegistry>, Error> {
+        let assets: HashMap<String, AssetInfo> = self.assets(&[]).await?;
+        let pairs: HashMap<String, AssetPairInfo> = self.asset_pairs(&[]).await?;
+        let registry: Arc<AssetRegistry> = Arc::new(AssetRegistry::new(assets, pairs));
+
+        *self.registry.write().await = Some(registry.clone());
+
+        Ok(registry)
+    }
+
+    /// Get ticker information for the given asset pairs (all pairs if empty).
+    pub async fn ticker(&self, pairs: &[&str]) -> Result<HashMap<String, TickerInfo>, Error> {
+        self.query_public(Api::Ticker { pairs }).await
+    }
+
+    /// Get OHLC data.
+    ///
+    /// Returns up to 720 of the most recent entries (older data cannot be retrieved).
+    pub async fn ohlc(
+        &self,
+        pair: &str,
+        interval: Option<OhlcInterval>,
+        since: Option<u64>,
+    ) -> Result<Ohlc, Error> {
+        self.query_public(Api::Ohlc {
+            pair,
+            interval,
+            since,
+        })
+        .await
+    }
+
+    /// Get order book.
+    pub async fn depth(
+        &self,
+        pair: &str,
+        count: Option<u16>,
+    ) -> Result<HashMap<String, OrderBook>, Error> {
+        self.query_public(Api::Depth { pair, count }).await
+    }
+
+    /// Get recent trades.
+    ///
+    /// Returns the last 1000 trades by default.
+    pub async fn recent_trades(
+        &self,
+        pair: &str,
+        since: Option<u64>,
+        count: Option<u16>,
+    ) -> Result<RecentTrades, Error> {
+        self.query_public(Api::Trades { pair, since, count }).await
+    }
+
+    /// Get recent spreads.
+    pub async fn recent_spreads(
+        &self,
+        pair: &str,
+        since: Option<u64>,
+    ) -> Result<RecentSpreads, Error> {
+        self.query_public(Api::Spread { pair, since }).await
+    }
+
+    /// Get balances of all assets.
+    pub async fn balances(&self) -> Result<HashMap<String, Amount>, Error> {
+        let balances: Balances = self.query_private(Api::Balance).await?;
+        Ok(balances.0)
+    }
+
+    /// Get extended balances of all assets, including credit and amount held in open orders.
+    pub async fn extended_balances(&self) -> Result<HashMap<String, ExtendedBalance>, Error> {
+        self.query_private(Api::BalanceEx).await
+    }
+
+    /// Get the margin trade balance, valued in the base asset (default: `ZUSD`).
+    pub async fn trade_balance(&self, asset: Option<&str>) -> Result<TradeBalance, Error> {
+        self.query_private(Api::TradeBalance { asset }).await
+    }
+
+    /// Get the 30-day trade volume, and the fee tiers of the given asset pairs (none if empty).
+    pub async fn trade_volume(&self, pairs: &[&str]) -> Result<TradeVolume, Error> {
+        self.query_private(Api::TradeVolume { pairs }).await
+    }
+
+    /// Get the open margin positions (all positions if `txids` is empty).
+    ///
+    /// Set `docalcs` to include the current value and the unrealized profit/loss of the positions.
+    pub async fn open_positions(
+        &self,
+        txids: &[&str],
+        docalcs: bool,
+    ) -> Result<HashMap<String, OpenPosition>, Error> {
+        self.query_private(Api::OpenPositions { txids, docalcs })
+            .await
     }
 
     /// Get **bitcoin** balance.
-    pub async fn balance(&self) -> Result<f64, Error> {
-        // Get bitcoin balances
-        let balances: BitcoinBalances = self.query_private(Api::Balance).await?;
+    pub async fn balance(&self) -> Result<Amount, Error> {
+        self.asset_balance(&AssetFamily::bitcoin()).await
+    }
 
-        // Sum balances
-        Ok(balances.sum())
+    /// Get the balance of the asset family (sum of the asset and its variants).
+    pub async fn asset_balance(&self, family: &AssetFamily) -> Result<Amount, Error> {
+        let balances: HashMap<String, Amount> = self.balances().await?;
+        Ok(family.sum(&balances))
     }
 
     /// Get **bitcoin** deposit transactions.
     pub async fn deposit_transactions(&self) -> Result<Vec<DepositTransaction>, Error> {
+        self.asset_deposit_transactions(&AssetFamily::bitcoin())
+            .await
+    }
+
+    /// Get **bitcoin** deposit transactions, filtered by the query options.
+    ///
+    /// Use [`Cursor`](crate::request::Cursor) to enable the paginated response.
+    pub async fn deposit_transactions_with_options(
+        &self,
+        options: &QueryOptions,
+    ) -> Result<FundingTransactions<DepositTransaction>, Error> {
+        self.asset_deposit_transactions_with_options(&AssetFamily::bitcoin(), options)
+            .await
+    }
+
+    /// Get deposit transactions of the asset family.
+    pub async fn asset_deposit_transactions(
+        &self,
+        family: &AssetFamily,
+    ) -> Result<Vec<DepositTransaction>, Error> {
+        let deposits: FundingTransactions<DepositTransaction> = self
+            .asset_deposit_transactions_with_options(family, &QueryOptions::default())
+            .await?;
+        Ok(deposits.transactions)
+    }
+
+    /// Get deposit transactions of the asset family, filtered by the query options.
+    ///
+    /// Use [`Cursor`](crate::request::Cursor) to enable the paginated response.
+    pub async fn asset_deposit_transactions_with_options(
+        &self,
+        family: &AssetFamily,
+        options: &QueryOptions,
+    ) -> Result<FundingTransactions<DepositTransaction>, Error> {
         self.query_private(Api::DepositStatus {
-            asset: Some(XBT_TICKER),
+            asset: Some(family.ticker()),
+            options,
+        })
+        .await
+    }
+
+    /// Get the deposit methods available for the asset.
+    pub async fn deposit_methods(&self, asset: &str) -> Result<Vec<DepositMethod>, Error> {
+        self.query_private(Api::DepositMethods { asset }).await
+    }
+
+    /// Get the existing deposit addresses of the asset, for the deposit method.
+    pub async fn deposit_addresses(
+        &self,
+        asset: &str,
+        method: &str,
+    ) -> Result<Vec<DepositAddress>, Error> {
+        self.query_private(Api::DepositAddresses {
+            asset,
+            method,
+            new: false,
+            amount: None,
         })
         .await
     }
 
+    /// Generate a new deposit address of the asset, for the deposit method.
+    ///
+    /// The `amount` is required for Lightning deposits: in this case, the address is an invoice.
+    pub async fn new_deposit_address(
+        &self,
+        asset: &str,
+        method: &str,
+        amount: Option<Amount>,
+    ) -> Result<DepositAddress, Error> {
+        let addresses: Vec<DepositAddress> = self
+            .query_private(Api::DepositAddresses {
+                asset,
+                method,
+                new: true,
+                amount,
+            })
+            .await?;
+        addresses.into_iter().next().ok_or(Error::MissingResult)
+    }
+
     /// Get **bitcoin** withdraw transactions.
     pub async fn withdraw_transactions(&self) -> Result<Vec<WithdrawTransaction>, Error> {
+        self.asset_withdraw_transactions(&AssetFamily::bitcoin())
+            .await
+    }
+
+    /// Get **bitcoin** withdraw transactions, filtered by the query options.
+    ///
+    /// Use [`Cursor`](crate::request::Cursor) to enable the paginated response.
+    pub async fn withdraw_transactions_with_options(
+        &self,
+        options: &QueryOptions,
+    ) -> Result<FundingTransactions<WithdrawTransaction>, Error> {
+        self.asset_withdraw_transactions_with_options(&AssetFamily::bitcoin(), options)
+            .await
+    }
+
+    /// Get withdraw transactions of the asset family.
+    pub async fn asset_withdraw_transactions(
+        &self,
+        family: &AssetFamily,
+    ) -> Result<Vec<WithdrawTransaction>, Error> {
+        let withdrawals: FundingTransactions<WithdrawTransaction> = self
+            .asset_withdraw_transactions_with_options(family, &QueryOptions::default())
+            .await?;
+        Ok(withdrawals.transactions)
+    }
+
+    /// Get withdraw transactions of the asset family, filtered by the query options.
+    ///
+    /// Use [`Cursor`](crate::request::Cursor) to enable the paginated response.
+    pub async fn asset_withdraw_transactions_with_options(
+        &self,
+        family: &AssetFamily,
+        options: &QueryOptions,
+    ) -> Result<FundingTransactions<WithdrawTransaction>, Error> {
         self.query_private(Api::WithdrawStatus {
-            asset: Some(XBT_TICKER),
+            asset: Some(family.ticker()),
+            options,
         })
         .await
     }
 
+    /// Get the withdrawal methods available for the asset.
+    pub async fn withdraw_methods(&self, asset: &str) -> Result<Vec<WithdrawMethod>, Error> {
+        self.query_private(Api::WithdrawMethods { asset }).await
+    }
+
+    /// Get the whitelisted withdrawal addresses of the asset, optionally filtered by withdrawal method.
+    pub async fn withdraw_addresses(
+        &self,
+        asset: &str,
+        method: Option<&str>,
+    ) -> Result<Vec<WithdrawAddress>, Error> {
+        self.query_private(Api::WithdrawAddresses { asset, method })
+            .await
+    }
+
+    /// Get a fee and limit quote for withdrawing the amount of the asset to the withdrawal key.
+    pub async fn withdraw_info(
+        &self,
+        asset: &str,
+        key: &str,
+        amount: Amount,
+    ) -> Result<WithdrawInfo, Error> {
+        self.query_private(Api::WithdrawInfo { asset, key, amount })
+            .await
+    }
+
+    /// Withdraw funds to a whitelisted withdrawal key.
+    ///
+    /// The returned reference ID matches the [`WithdrawTransaction::id`] of the withdraw transaction.
+    pub async fn withdraw(&self, withdraw: &Withdraw) -> Result<WithdrawResult, Error> {
+        self.query_private(Api::Withdraw(withdraw)).await
+    }
+
+    /// Cancel a recently requested withdrawal, if it has not already been successfully processed.
+    pub async fn cancel_withdrawal(&self, asset: &str, refid: &str) -> Result<bool, Error> {
+        self.query_private(Api::WithdrawCancel { asset, refid })
+            .await
+    }
+
+    /// Stream the trade history pages (**all** assets), filtered by the query options.
+    ///
+    /// Kraken returns at most 50 trades per page: the pages are fetched lazily, using the `ofs` parameter.
+    pub fn trade_history_pages<'a>(
+        &'a self,
+        options: &'a QueryOptions,
+    ) -> impl Stream<Item = Result<TradesHistory, Error>> + 'a {
+        self.paginate(move |ofs| Api::TradesHistory {
+            options,
+            ofs: Some(ofs),
+        })
+    }
+
     /// Get **bitcoin** trade history.
     pub async fn trade_history(&self) -> Result<Vec<Trade>, Error> {
-        let history: TradesHistory = self
-            .query_private(Api::TradesHistory {
-                start: None,
-                end: None,
-            })
-            .await?;
+        self.trade_history_with_options(&QueryOptions::default())
+            .await
+    }
+
+    /// Get **bitcoin** trade history, filtered by the query options.
+    pub async fn trade_history_with_options(
+        &self,
+        options: &QueryOptions,
+    ) -> Result<Vec<Trade>, Error> {
+        self.asset_trade_history_with_options(&AssetFamily::bitcoin(), options)
+            .await
+    }
 
-        // Filter for trades involving Bitcoin (checking if pair contains the ticker)
-        let trades: Vec<Trade> = history
-            .trades
-            .into_values()
-            .filter(|trade| trade.pair.contains(XBT_TICKER))
-            .collect();
+    /// Get trade history of the asset family, as base or quote asset.
+    pub async fn asset_trade_history(&self, family: &AssetFamily) -> Result<Vec<Trade>, Error> {
+        self.asset_trade_history_with_options(family, &QueryOptions::default())
+            .await
+    }
+
+    /// Get trade history of the asset family, as base or quote asset, filtered by the query options.
+    ///
+    /// The pairs of the trades are resolved with the [`asset_registry`](Self::asset_registry), if already fetched:
+    /// otherwise, they are matched by prefix and suffix (see [`AssetFamily::matches_pair`]).
+    pub async fn asset_trade_history_with_options(
+        &self,
+        family: &AssetFamily,
+        options: &QueryOptions,
+    ) -> Result<Vec<Trade>, Error> {
+        // Don't fetch the registry: the public endpoints would be a dependency of the private history
+        let registry: Arc<AssetRegistry> = self.registry.read().await.clone().unwrap_or_default();
+
+        let mut pages = pin!(self.trade_history_pages(options));
+        let mut trades: Vec<Trade> = Vec::new();
+
+        while let Some(history) = pages.try_next().await? {
+            trades.extend(
+                history
+                    .trades
+                    .into_values()
+                    .filter(|trade| family.matches_pair(&registry, &trade.pair)),
+            );
+        }
 
         Ok(trades)
     }
+
+    /// Stream the ledgers pages, filtered by the query options.
+    ///
+    /// Kraken returns at most 50 ledger entries per page: the pages are fetched lazily, using the `ofs` parameter.
+    pub fn ledgers_pages<'a>(
+        &'a self,
+        options: &'a LedgersOptions,
+    ) -> impl Stream<Item = Result<Ledgers, Error>> + 'a {
+        self.paginate(move |ofs| Api::Ledgers {
+            options,
+            ofs: Some(ofs),
+        })
+    }
+
+    /// Get all the ledger entries (by ledger ID), filtered by the query options.
+    pub async fn ledgers(
+        &self,
+        options: &LedgersOptions,
+    ) -> Result<HashMap<String, LedgerEntry>, Error> {
+        let mut pages = pin!(self.ledgers_pages(options));
+        let mut ledger: HashMap<String, LedgerEntry> = HashMap::new();
+
+        while let Some(page) = pages.try_next().await? {
+            ledger.extend(page.ledger);
+        }
+
+        Ok(ledger)
+    }
+
+    /// Get ledger entries by ID (max 20).
+    pub async fn query_ledgers(&self, ids: &[&str]) -> Result<HashMap<String, LedgerEntry>, Error> {
+        self.query_private(Api::QueryLedgers { ids }).await
+    }
+
+    /// Place a new order.
+    ///
+    /// Returns [`Error::InvalidOrder`], without sending the request, if the order is inconsistent
+    /// (i.e., a good till date order without expiration time).
+    pub async fn add_order(&self, order: &AddOrder) -> Result<AddOrderResult, Error> {
+        order.check()?;
+        self.query_private(Api::AddOrder(order)).await
+    }
+
+    /// Edit an open order.
+    pub async fn edit_order(&self, order: &EditOrder) -> Result<EditOrderResult, Error> {
+        self.query_private(Api::EditOrder(order)).await
+    }
+
+    /// Cancel an open order by transaction ID, user reference or client order ID.
+    pub async fn cancel_order(&self, txid: &str) -> Result<CancelOrderResult, Error> {
+        self.query_private(Api::CancelOrder { txid }).await
+    }
+
+    /// Cancel all open orders.
+    pub async fn cancel_all_orders(&self) -> Result<CancelOrderResult, Error> {
+        self.query_private(Api::CancelAll).await
+    }
+
+    /// Cancel all open orders after the timeout (in seconds), unless the timer is extended.
+    ///
+    /// This is a "dead man's switch": call it periodically to extend the timer, or with `0` to disable it.
+    pub async fn cancel_all_orders_after(
+        &self,
+        timeout: u64,
+    ) -> Result<CancelAllOrdersAfterResult, Error> {
+        self.query_private(Api::CancelAllOrdersAfter { timeout })
+            .await
+    }
+
+    /// Get open orders (by transaction ID).
+    pub async fn open_orders(&self) -> Result<HashMap<String, OrderInfo>, Error> {
+        let orders: OpenOrders = self.query_private(Api::OpenOrders).await?;
+        Ok(orders.open)
+    }
+
+    /// Stream the closed orders pages, filtered by the query options.
+    ///
+    /// Kraken returns at most 50 orders per page: the pages are fetched lazily, using the `ofs` parameter.
+    pub fn closed_orders_pages<'a>(
+        &'a self,
+        options: &'a QueryOptions,
+    ) -> impl Stream<Item = Result<ClosedOrders, Error>> + 'a {
+        self.paginate(move |ofs| Api::ClosedOrders {
+            options,
+            ofs: Some(ofs),
+        })
+    }
+
+    /// Get all the closed orders (by transaction ID), filtered by the query options.
+    pub async fn closed_orders(
+        &self,
+        options: &QueryOptions,
+    ) -> Result<HashMap<String, OrderInfo>, Error> {
+        let mut pages = pin!(self.closed_orders_pages(options));
+        let mut orders: HashMap<String, OrderInfo> = HashMap::new();
+
+        while let Some(page) = pages.try_next().await? {
+            orders.extend(page.closed);
+        }
+
+        Ok(orders)
+    }
+
+    /// Get orders by transaction ID (max 50).
+    pub async fn query_orders(&self, txids: &[&str]) -> Result<HashMap<String, OrderInfo>, Error> {
+        self.query_private(Api::QueryOrders { txids }).await
+    }
+
+    /// Get a token to authenticate the WebSocket connections.
+    pub async fn websockets_token(&self) -> Result<WebSocketsToken, Error> {
+        self.query_private(Api::GetWebSocketsToken).await
+    }
+
+    /// Get the earn strategies (staking, opt-in rewards), filtered by the query options.
+    pub async fn earn_strategies(
+        &self,
+        options: &EarnStrategiesOptions,
+    ) -> Result<EarnStrategies, Error> {
+        self.query_private(Api::EarnStrategies(options)).await
+    }
+
+    /// Allocate the amount to the earn strategy.
+    ///
+    /// The allocation is processed asynchronously: check it with [`earn_allocate_status`](Self::earn_allocate_status).
+    pub async fn earn_allocate(&self, strategy_id: &str, amount: Amount) -> Result<bool, Error> {
+        self.query_private(Api::EarnAllocate {
+            strategy_id,
+            amount,
+        })
+        .await
+    }
+
+    /// Deallocate the amount from the earn strategy.
+    ///
+    /// The deallocation is processed asynchronously: check it with [`earn_deallocate_status`](Self::earn_deallocate_status).
+    pub async fn earn_deallocate(&self, strategy_id: &str, amount: Amount) -> Result<bool, Error> {
+        self.query_private(Api::EarnDeallocate {
+            strategy_id,
+            amount,
+        })
+        .await
+    }
+
+    /// Get the status of the last allocation request to the earn strategy.
+    pub async fn earn_allocate_status(
+        &self,
+        strategy_id: &str,
+    ) -> Result<EarnOperationStatus, Error> {
+        self.query_private(Api::EarnAllocateStatus { strategy_id })
+            .await
+    }
+
+    /// Get the status of the last deallocation request from the earn strategy.
+    pub async fn earn_deallocate_status(
+        &self,
+        strategy_id: &str,
+    ) -> Result<EarnOperationStatus, Error> {
+        self.query_private(Api::EarnDeallocateStatus { strategy_id })
+            .await
+    }
+
+    /// Get the earn allocations, with the rewards, filtered by the query options.
+    pub async fn earn_allocations(
+        &self,
+        options: &EarnAllocationsOptions,
+    ) -> Result<EarnAllocations, Error> {
+        self.query_private(Api::EarnAllocations(options)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::TradeType;
+
+    #[test]
+    fn test_endpoint() {
+        let client = KrakenClient::new(KrakenAuth::None).unwrap();
+        assert_eq!(
+            client.endpoint("/0/public/Ticker").unwrap().as_str(),
+            "https://api.kraken.com/0/public/Ticker"
+        );
+
+        // Path prefix
+        let client = KrakenClient::builder()
+            .root_url(Url::parse("http://localhost:8080/kraken").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.endpoint("/0/private/Balance").unwrap().as_str(),
+            "http://localhost:8080/kraken/0/private/Balance"
+        );
+    }
+
+    #[test]
+    fn test_trades_history_body() {
+        let options = QueryOptions::new();
+        let api = Api::TradesHistory {
+            options: &options,
+            ofs: None,
+        };
+        let json = serde_json::to_string(&api.body()).unwrap();
+        assert_eq!(json, r#"{"type":"all","trades":true}"#);
+
+        let options = QueryOptions::new()
+            .trade_type(TradeType::NoPosition)
+            .end(1688669597);
+        let api = Api::TradesHistory {
+            options: &options,
+            ofs: Some(50),
+        };
+        let json = serde_json::to_string(&api.body()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"no position","trades":true,"end":1688669597,"ofs":50}"#
+        );
+
+        // The trade type isn't sent to the funding history
+        let api = Api::DepositStatus {
+            asset: Some("XBT"),
+            options: &options,
+        };
+        let json = serde_json::to_string(&api.body()).unwrap();
+        assert_eq!(json, r#"{"asset":"XBT","end":1688669597}"#);
+    }
 }
@@ -7,6 +7,12 @@ pub(crate) const API_ROOT_URL: &str = "https://api.kraken.com";
 
 pub(crate) const API_VERSION: u16 = 0;
 
+#[cfg(feature = "websocket")]
+pub(crate) const WS_PUBLIC_URL: &str = "wss://ws.kraken.com/v2";
+
+#[cfg(feature = "websocket")]
+pub(crate) const WS_AUTH_URL: &str = "wss://ws-auth.kraken.com/v2";
+
 /// User Agent for the client
 pub(super) const USER_AGENT_NAME: &str =
     concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
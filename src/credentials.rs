@@ -0,0 +1,316 @@
+//! Credentials loading
+//!
+//! The credentials can be loaded from the environment, or from a file:
+//! * TOML (`.toml`) or JSON (`.json`) file, with one table per profile;
+//! * Kraken key file (any other extension), with the API key on the first line and the secret on the second one.
+//!
+//! ```toml
+//! [treasury]
+//! key = "<api-key>"
+//! secret = "<secret>"
+//!
+//! [trading]
+//! key = "<api-key>"
+//! secret = "<secret>"
+//! totp_secret = "<base32 secret>"
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::{env, fs};
+
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
+use crate::auth::{KrakenApiCredentials, KrakenAuth, Otp};
+use crate::error::Error;
+
+/// Name of the default profile
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Names of the environment variables holding the credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVarNames {
+    /// API key (default: `KRAKEN_API_KEY`)
+    pub key: String,
+    /// API secret (default: `KRAKEN_API_SECRET`)
+    pub secret: String,
+    /// Static two-factor password, optional (default: `KRAKEN_API_OTP`)
+    pub otp: String,
+    /// Base32 TOTP secret, optional (default: `KRAKEN_API_TOTP_SECRET`)
+    pub totp_secret: String,
+}
+
+impl Default for EnvVarNames {
+    fn default() -> Self {
+        Self::with_prefix("KRAKEN")
+    }
+}
+
+impl EnvVarNames {
+    /// Variable names with a custom prefix (i.e., `TREASURY` for `TREASURY_API_KEY`, `TREASURY_API_SECRET`, ...)
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            key: format!("{prefix}_API_KEY"),
+            secret: format!("{prefix}_API_SECRET"),
+            otp: format!("{prefix}_API_OTP"),
+            totp_secret: format!("{prefix}_API_TOTP_SECRET"),
+        }
+    }
+}
+
+/// Credentials profile
+#[derive(Deserialize)]
+struct Profile {
+    key: String,
+    secret: Zeroizing<String>,
+    #[serde(default)]
+    otp: Option<Zeroizing<String>>,
+    #[serde(default)]
+    totp_secret: Option<Zeroizing<String>>,
+}
+
+impl Profile {
+    fn into_auth(self) -> Result<KrakenAuth, Error> {
+        let mut credentials = KrakenApiCredentials::new(self.key, self.secret.as_str())?;
+
+        if let Some(otp) = otp(self.otp, self.totp_secret)? {
+            credentials = credentials.otp(otp);
+        }
+
+        Ok(KrakenAuth::ApiKeys(credentials))
+    }
+}
+
+impl KrakenAuth {
+    /// Load the API key credentials from the default environment variables (see [`EnvVarNames`])
+    pub fn from_env() -> Result<Self, Error> {
+        Self::from_env_with(&EnvVarNames::default())
+    }
+
+    /// Load the API key credentials from custom environment variables
+    pub fn from_env_with(names: &EnvVarNames) -> Result<Self, Error> {
+        let profile: Profile = Profile {
+            key: required_var(&names.key)?,
+            secret: Zeroizing::new(required_var(&names.secret)?),
+            otp: optional_var(&names.otp)?.map(Zeroizing::new),
+            totp_secret: optional_var(&names.totp_secret)?.map(Zeroizing::new),
+        };
+
+        profile.into_auth()
+    }
+
+    /// Load the API key credentials of the default profile from a file
+    pub fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_file_profile(path, DEFAULT_PROFILE)
+    }
+
+    /// Load the API key credentials of a profile from a file
+    ///
+    /// On unix, the file must not be accessible by the group and by the others (i.e., mode `600`).
+    /// Kraken key files contain only the default profile.
+    pub fn from_file_profile<P>(path: P, profile: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path: &Path = path.as_ref();
+
+        check_permissions(path)?;
+
+        let content: Zeroizing<String> = Zeroizing::new(fs::read_to_string(path)?);
+
+        let profile: Profile = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext @ ("toml" | "json")) => {
+                // Drop the parse error: it may quote the content of the file
+                let profiles: Option<HashMap<String, Profile>> = match ext {
+                    "toml" => toml::from_str(&content).ok(),
+                    _ => serde_json::from_str(&content).ok(),
+                };
+
+                let mut profiles: HashMap<String, Profile> =
+                    profiles.ok_or_else(|| Error::InvalidProfileFile {
+                        path: path.to_path_buf(),
+                        profile: profile.to_string(),
+                    })?;
+
+                profiles
+                    .remove(profile)
+                    .ok_or_else(|| Error::ProfileNotFound(profile.to_string()))?
+            }
+            _ => {
+                if profile != DEFAULT_PROFILE {
+                    return Err(Error::ProfileNotFound(profile.to_string()));
+                }
+
+                parse_key_file(&content)?
+            }
+        };
+
+        profile.into_auth()
+    }
+}
+
+/// Parse a Kraken key file: API key on the first line, secret on the second one
+fn parse_key_file(content: &str) -> Result<Profile, Error> {
+    let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    match (lines.next(), lines.next()) {
+        (Some(key), Some(secret)) => Ok(Profile {
+            key: key.to_string(),
+            secret: Zeroizing::new(secret.to_string()),
+            otp: None,
+            totp_secret: None,
+        }),
+        _ => Err(Error::InvalidKeyFile),
+    }
+}
+
+fn otp(
+    password: Option<Zeroizing<String>>,
+    totp_secret: Option<Zeroizing<String>>,
+) -> Result<Option<Otp>, Error> {
+    match (password, totp_secret) {
+        (_, Some(secret)) => Ok(Some(Otp::totp(&secret)?)),
+        (Some(password), None) => Ok(Some(Otp::password(password.as_str()))),
+        (None, None) => Ok(None),
+    }
+}
+
+fn required_var(name: &str) -> Result<String, Error> {
+    optional_var(name)?.ok_or_else(|| Error::MissingEnvVar(name.to_string()))
+}
+
+fn optional_var(name: &str) -> Result<Option<String>, Error> {
+    match env::var(name) {
+        Ok(value) if !value.trim().is_empty() => Ok(Some(value)),
+        Ok(..) | Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(..)) => Err(Error::InvalidEnvVar(name.to_string())),
+    }
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode: u32 = fs::metadata(path)?.permissions().mode();
+
+    if mode & 0o077 != 0 {
+        return Err(Error::InsecurePermissions(path.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const SECRET: &str =
+        "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+
+    fn write_file(name: &str, content: &str, mode: u32) -> PathBuf {
+        let path = env::temp_dir().join(format!("kraken-{}-{name}", std::process::id()));
+        fs::write(&path, content).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+
+        path
+    }
+
+    fn key(auth: &KrakenAuth) -> &str {
+        match auth {
+            KrakenAuth::ApiKeys(credentials) => &credentials.key,
+            KrakenAuth::None => panic!("Expected API keys"),
+        }
+    }
+
+    #[test]
+    fn test_from_toml_file() {
+        let content = format!(
+            "[treasury]\nkey = \"treasury-key\"\nsecret = \"{SECRET}\"\n\n[trading]\nkey = \"trading-key\"\nsecret = \"{SECRET}\"\ntotp_secret = \"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\"\n"
+        );
+        let path = write_file("profiles.toml", &content, 0o600);
+
+        let auth = KrakenAuth::from_file_profile(&path, "treasury").unwrap();
+        assert_eq!(key(&auth), "treasury-key");
+
+        let auth = KrakenAuth::from_file_profile(&path, "trading").unwrap();
+        assert_eq!(key(&auth), "trading-key");
+
+        assert!(matches!(
+            KrakenAuth::from_file(&path),
+            Err(Error::ProfileNotFound(..))
+        ));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_profile_file_redacted() {
+        let content = format!("[default]\nkey = \"my-key\"\nsecret = \"{SECRET}\n");
+        let path = write_file("invalid.toml", &content, 0o600);
+
+        let err = KrakenAuth::from_file(&path).unwrap_err();
+        assert!(matches!(err, Error::InvalidProfileFile { .. }));
+        assert!(!err.to_string().contains(SECRET));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_from_json_file() {
+        let content = format!(r#"{{"default": {{"key": "json-key", "secret": "{SECRET}"}}}}"#);
+        let path = write_file("profiles.json", &content, 0o600);
+
+        let auth = KrakenAuth::from_file(&path).unwrap();
+        assert_eq!(key(&auth), "json-key");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_from_key_file() {
+        let content = format!("my-key\n{SECRET}\n");
+        let path = write_file("kraken.key", &content, 0o600);
+
+        let auth = KrakenAuth::from_file(&path).unwrap();
+        assert_eq!(key(&auth), "my-key");
+
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            parse_key_file("my-key\n"),
+            Err(Error::InvalidKeyFile)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_insecure_permissions() {
+        let content = format!("my-key\n{SECRET}\n");
+        let path = write_file("insecure.key", &content, 0o644);
+
+        assert!(matches!(
+            KrakenAuth::from_file(&path),
+            Err(Error::InsecurePermissions(..))
+        ));
+
+        fs::remove_file(path).unwrap();
+    }
+}
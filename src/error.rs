@@ -1,11 +1,18 @@
 //! Kraken error
 
+use std::fmt;
+use std::str::FromStr;
+
+use reqwest::StatusCode;
 use reqwest::header::InvalidHeaderValue;
 use thiserror::Error;
 
 /// Kraken error
 #[derive(Debug, Error)]
 pub enum Error {
+    /// I/O error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     /// Base64 error
     #[error(transparent)]
     Base64(#[from] base64::DecodeError),
@@ -22,12 +29,422 @@ pub enum Error {
     #[error(transparent)]
     Url(#[from] url::ParseError),
     /// Kraken response errors
-    #[error("{0:?}")]
-    Kraken(Vec<String>),
+    ///
+    /// Returned if at least one entry has the error severity: the warnings alone don't make the request fail,
+    /// and are passed to the [`warning_handler`](crate::builder::KrakenClientBuilder::warning_handler) instead.
+    #[error("{:?}", .0.iter().map(|e| e.raw.as_str()).collect::<Vec<_>>())]
+    Kraken(Vec<KrakenError>),
     /// Missing credentials
     #[error("missing credentials")]
     MissingCredentials,
+    /// Missing environment variable
+    #[error("missing environment variable: {0}")]
+    MissingEnvVar(String),
+    /// Environment variable not valid unicode
+    #[error("invalid environment variable: {0}")]
+    InvalidEnvVar(String),
+    /// Credentials profile not found
+    #[error("profile not found: {0}")]
+    ProfileNotFound(String),
+    /// Invalid credentials profile file (the parse error isn't kept, as it may quote the secret)
+    #[error("invalid profile file {} (profile: {profile})", .path.display())]
+    InvalidProfileFile {
+        /// Path of the file
+        path: std::path::PathBuf,
+        /// Requested profile
+        profile: String,
+    },
+    /// Invalid Kraken key file
+    #[error("invalid key file: expected the API key and the secret on two lines")]
+    InvalidKeyFile,
+    /// Credentials file accessible by other users
+    #[error("insecure permissions for {}: must not be accessible by group and others", .0.display())]
+    InsecurePermissions(std::path::PathBuf),
+    /// Invalid nonce file (not reset, as the next nonce could be lower than one already used)
+    #[error("invalid nonce file {}: expected the last nonce", .0.display())]
+    InvalidNonceFile(std::path::PathBuf),
+    /// Signer error
+    #[error("signer: {0}")]
+    Signer(String),
+    /// Invalid base32 string
+    #[error("invalid base32")]
+    InvalidBase32,
+    /// Invalid amount (not a finite number)
+    #[error("invalid amount: {0}")]
+    InvalidAmount(String),
+    /// Invalid order request
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
     /// Missing result in response
     #[error("missing result")]
     MissingResult,
+    /// WebSocket error
+    #[cfg(feature = "websocket")]
+    #[error(transparent)]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    /// WebSocket request rejected
+    #[cfg(feature = "websocket")]
+    #[error("websocket request failed: {0}")]
+    WebSocketRequest(String),
+    /// WebSocket disconnected
+    #[cfg(feature = "websocket")]
+    #[error("websocket disconnected")]
+    WebSocketDisconnected,
+}
+
+#[cfg(feature = "websocket")]
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(e))
+    }
+}
+
+impl Error {
+    /// Iterate over the Kraken errors (empty if this isn't a [`Error::Kraken`] error)
+    fn kraken_errors(&self) -> impl Iterator<Item = &KrakenError> {
+        let errors: &[KrakenError] = match self {
+            Self::Kraken(errors) => errors,
+            _ => &[],
+        };
+        errors.iter()
+    }
+
+    /// Check if the error is caused by the API rate limit
+    pub fn is_rate_limited(&self) -> bool {
+        self.kraken_errors().any(KrakenError::is_rate_limited)
+    }
+
+    /// Check if the error is caused by an invalid nonce
+    pub fn is_invalid_nonce(&self) -> bool {
+        self.kraken_errors()
+            .any(|e| e.message == KrakenErrorMessage::InvalidNonce)
+    }
+
+    /// Check if the request that caused the error can be retried
+    ///
+    /// This is the case for rate limits (including HTTP 429), invalid nonces (a new nonce is generated at every request),
+    /// temporary service outages (including HTTP 5xx) and network timeouts.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Reqwest(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status()
+                        .is_some_and(|s| s.is_server_error() || s == StatusCode::TOO_MANY_REQUESTS)
+            }
+            Self::Kraken(errors) => errors.iter().any(KrakenError::is_retryable),
+            _ => false,
+        }
+    }
+}
+
+/// Kraken error severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KrakenErrorSeverity {
+    /// Error (`E` prefix)
+    Error,
+    /// Warning (`W` prefix)
+    Warning,
+}
+
+/// Kraken error category
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KrakenErrorCategory {
+    /// General
+    General,
+    /// API
+    Api,
+    /// Query
+    Query,
+    /// Order
+    Order,
+    /// Trade
+    Trade,
+    /// Funding
+    Funding,
+    /// Service
+    Service,
+    /// Session
+    Session,
+    /// Authentication
+    Auth,
+    /// Unknown category
+    Unknown(String),
+}
+
+impl From<&str> for KrakenErrorCategory {
+    fn from(category: &str) -> Self {
+        match category {
+            "General" => Self::General,
+            "API" => Self::Api,
+            "Query" => Self::Query,
+            "Order" => Self::Order,
+            "Trade" => Self::Trade,
+            "Funding" => Self::Funding,
+            "Service" => Self::Service,
+            "Session" => Self::Session,
+            "Auth" => Self::Auth,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+/// Known Kraken error messages
+///
+/// <https://docs.kraken.com/api/docs/guides/spot-errors>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KrakenErrorMessage {
+    /// Invalid arguments
+    InvalidArguments,
+    /// Permission denied
+    PermissionDenied,
+    /// Unknown method
+    UnknownMethod,
+    /// Internal error
+    InternalError,
+    /// Too many requests
+    TooManyRequests,
+    /// Invalid key
+    InvalidKey,
+    /// Invalid signature
+    InvalidSignature,
+    /// Invalid nonce
+    InvalidNonce,
+    /// Rate limit exceeded
+    RateLimitExceeded,
+    /// Feature disabled
+    FeatureDisabled,
+    /// Service unavailable
+    Unavailable,
+    /// Service busy
+    Busy,
+    /// Deadline elapsed
+    DeadlineElapsed,
+    /// Market in cancel only mode
+    MarketInCancelOnlyMode,
+    /// Market in post only mode
+    MarketInPostOnlyMode,
+    /// Unknown asset pair
+    UnknownAssetPair,
+    /// Unknown asset
+    UnknownAsset,
+    /// Insufficient funds
+    InsufficientFunds,
+    /// Insufficient margin
+    InsufficientMargin,
+    /// Order minimum not met
+    OrderMinimumNotMet,
+    /// Cost minimum not met
+    CostMinimumNotMet,
+    /// Tick size check failed
+    TickSizeCheckFailed,
+    /// Orders limit exceeded
+    OrdersLimitExceeded,
+    /// Domain rate limit exceeded
+    DomainRateLimitExceeded,
+    /// Unknown order
+    UnknownOrder,
+    /// Invalid order
+    InvalidOrder,
+    /// Unknown withdraw key
+    UnknownWithdrawKey,
+    /// Invalid amount
+    InvalidAmount,
+    /// Max fee exceeded
+    MaxFeeExceeded,
+    /// Unknown message
+    Unknown(String),
+}
+
+impl From<&str> for KrakenErrorMessage {
+    fn from(message: &str) -> Self {
+        match message {
+            "Invalid arguments" => Self::InvalidArguments,
+            "Permission denied" => Self::PermissionDenied,
+            "Unknown method" => Self::UnknownMethod,
+            "Internal error" => Self::InternalError,
+            "Too many requests" => Self::TooManyRequests,
+            "Invalid key" => Self::InvalidKey,
+            "Invalid signature" => Self::InvalidSignature,
+            "Invalid nonce" => Self::InvalidNonce,
+            "Rate limit exceeded" => Self::RateLimitExceeded,
+            "Feature disabled" => Self::FeatureDisabled,
+            "Unavailable" => Self::Unavailable,
+            "Busy" => Self::Busy,
+            "Deadline elapsed" => Self::DeadlineElapsed,
+            "Market in cancel_only mode" => Self::MarketInCancelOnlyMode,
+            "Market in post_only mode" => Self::MarketInPostOnlyMode,
+            "Unknown asset pair" => Self::UnknownAssetPair,
+            "Unknown asset" => Self::UnknownAsset,
+            "Insufficient funds" => Self::InsufficientFunds,
+            "Insufficient margin" => Self::InsufficientMargin,
+            "Order minimum not met" => Self::OrderMinimumNotMet,
+            "Cost minimum not met" => Self::CostMinimumNotMet,
+            "Tick size check failed" => Self::TickSizeCheckFailed,
+            "Orders limit exceeded" => Self::OrdersLimitExceeded,
+            "Domain rate limit exceeded" => Self::DomainRateLimitExceeded,
+            "Unknown order" => Self::UnknownOrder,
+            "Invalid order" => Self::InvalidOrder,
+            "Unknown withdraw key" => Self::UnknownWithdrawKey,
+            "Invalid amount" => Self::InvalidAmount,
+            "Max fee exceeded" => Self::MaxFeeExceeded,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+/// Structured Kraken error
+///
+/// Kraken errors have the `<severity><category>:<message>[:<detail>]` format (i.e., `EAPI:Invalid nonce`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KrakenError {
+    /// Severity
+    pub severity: KrakenErrorSeverity,
+    /// Category
+    pub category: KrakenErrorCategory,
+    /// Message
+    pub message: KrakenErrorMessage,
+    /// Additional details (i.e., the argument name for `EGeneral:Invalid arguments:<name>`)
+    pub detail: Option<String>,
+    /// Raw error string
+    pub raw: String,
+}
+
+impl KrakenError {
+    /// Check if the error is a rate limit error
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(
+            self.message,
+            KrakenErrorMessage::RateLimitExceeded
+                | KrakenErrorMessage::DomainRateLimitExceeded
+                | KrakenErrorMessage::TooManyRequests
+        )
+    }
+
+    /// Check if the request that caused the error can be retried
+    pub fn is_retryable(&self) -> bool {
+        self.is_rate_limited()
+            || matches!(
+                self.message,
+                KrakenErrorMessage::InvalidNonce
+                    | KrakenErrorMessage::Unavailable
+                    | KrakenErrorMessage::Busy
+                    | KrakenErrorMessage::DeadlineElapsed
+                    | KrakenErrorMessage::InternalError
+            )
+    }
+}
+
+impl FromStr for KrakenError {
+    type Err = std::convert::Infallible;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(raw))
+    }
+}
+
+impl From<&str> for KrakenError {
+    fn from(raw: &str) -> Self {
+        let (severity, rest) = match raw.split_at_checked(1) {
+            Some(("W", rest)) => (KrakenErrorSeverity::Warning, rest),
+            Some(("E", rest)) => (KrakenErrorSeverity::Error, rest),
+            _ => (KrakenErrorSeverity::Error, raw),
+        };
+
+        let (category, message, detail) = match rest.split_once(':') {
+            Some((category, message)) => match message.split_once(':') {
+                // Some messages have a detail (i.e., `EGeneral:Invalid arguments:ordertype`)
+                Some((message, detail))
+                    if !matches!(
+                        KrakenErrorMessage::from(message),
+                        KrakenErrorMessage::Unknown(..)
+                    ) =>
+                {
+                    (category, message, Some(detail.to_string()))
+                }
+                _ => (category, message, None),
+            },
+            None => ("", rest, None),
+        };
+
+        Self {
+            severity,
+            category: KrakenErrorCategory::from(category),
+            message: KrakenErrorMessage::from(message),
+            detail,
+            raw: raw.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for KrakenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kraken_error() {
+        let err: KrakenError = "EAPI:Invalid nonce".parse().unwrap();
+        assert_eq!(err.severity, KrakenErrorSeverity::Error);
+        assert_eq!(err.category, KrakenErrorCategory::Api);
+        assert_eq!(err.message, KrakenErrorMessage::InvalidNonce);
+        assert_eq!(err.detail, None);
+        assert_eq!(err.to_string(), "EAPI:Invalid nonce");
+
+        let err: KrakenError = "EGeneral:Invalid arguments:ordertype".parse().unwrap();
+        assert_eq!(err.category, KrakenErrorCategory::General);
+        assert_eq!(err.message, KrakenErrorMessage::InvalidArguments);
+        assert_eq!(err.detail.as_deref(), Some("ordertype"));
+
+        let err: KrakenError = "WOrder:Something new".parse().unwrap();
+        assert_eq!(err.severity, KrakenErrorSeverity::Warning);
+        assert_eq!(err.category, KrakenErrorCategory::Order);
+        assert_eq!(
+            err.message,
+            KrakenErrorMessage::Unknown(String::from("Something new"))
+        );
+    }
+
+    #[test]
+    fn test_error_helpers() {
+        let err = Error::Kraken(vec!["EAPI:Rate limit exceeded".parse().unwrap()]);
+        assert!(err.is_rate_limited());
+        assert!(err.is_retryable());
+        assert!(!err.is_invalid_nonce());
+
+        let err = Error::Kraken(vec!["EAPI:Invalid nonce".parse().unwrap()]);
+        assert!(err.is_invalid_nonce());
+        assert!(err.is_retryable());
+
+        let err = Error::Kraken(vec!["EOrder:Insufficient funds".parse().unwrap()]);
+        assert!(!err.is_rate_limited());
+        assert!(!err.is_retryable());
+
+        assert!(!Error::MissingCredentials.is_retryable());
+    }
+
+    #[test]
+    fn test_http_status_retryable() {
+        fn status_error(status: u16) -> Error {
+            let response = http::Response::builder()
+                .status(status)
+                .body(String::new())
+                .unwrap();
+            reqwest::Response::from(response)
+                .error_for_status()
+                .unwrap_err()
+                .into()
+        }
+
+        assert!(status_error(500).is_retryable());
+        assert!(status_error(503).is_retryable());
+        assert!(status_error(429).is_retryable());
+        assert!(!status_error(403).is_retryable());
+        assert!(!status_error(404).is_retryable());
+    }
 }
@@ -5,10 +5,21 @@
 #![warn(clippy::large_futures)]
 #![warn(rustdoc::bare_urls)]
 
+pub mod amount;
+pub mod asset;
 pub mod auth;
+pub mod builder;
 pub mod client;
 pub mod constant;
+pub mod credentials;
 pub mod error;
+pub mod nonce;
 pub mod prelude;
-mod request;
+pub mod rate_limit;
+pub mod registry;
+pub mod request;
 pub mod response;
+pub mod retry;
+pub mod signer;
+#[cfg(feature = "websocket")]
+pub mod ws;
@@ -0,0 +1,224 @@
+//! Nonce providers
+//!
+//! Kraken requires the nonce of every private request to be greater than the previous one, per API key.
+
+use std::fmt;
+#[cfg(feature = "file-nonce")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "file-nonce")]
+use std::io::{self, Read, Seek, SeekFrom, Write};
+#[cfg(feature = "file-nonce")]
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::SystemTime;
+
+#[cfg(feature = "file-nonce")]
+use fs4::fs_std::FileExt;
+use futures_util::future::{self, BoxFuture};
+#[cfg(feature = "file-nonce")]
+use tokio::task;
+
+use crate::error::Error;
+
+/// Process-wide nonce provider, shared by all the clients not configured with a custom one
+static SHARED: LazyLock<Arc<AtomicNonceProvider>> =
+    LazyLock::new(|| Arc::new(AtomicNonceProvider::new()));
+
+/// Nonce provider
+pub trait NonceProvider: fmt::Debug + Send + Sync {
+    /// Get the next nonce: must be strictly greater than all the previous ones.
+    fn next_nonce(&self) -> BoxFuture<'_, Result<u64, Error>>;
+}
+
+/// Strictly increasing nonce, based on the current time in milliseconds
+///
+/// Concurrent requests in the same millisecond, or a clock stepping back, get the last nonce plus one.
+#[derive(Debug, Default)]
+pub struct AtomicNonceProvider {
+    last: AtomicU64,
+}
+
+impl AtomicNonceProvider {
+    /// Construct a new provider
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the process-wide provider
+    pub fn shared() -> Arc<Self> {
+        SHARED.clone()
+    }
+
+    fn next(&self) -> u64 {
+        let now: u64 = now_millis();
+        let mut last: u64 = self.last.load(Ordering::Relaxed);
+
+        loop {
+            let next: u64 = now.max(last.saturating_add(1));
+
+            match self
+                .last
+                .compare_exchange_weak(last, next, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(..) => return next,
+                Err(current) => last = current,
+            }
+        }
+    }
+}
+
+impl NonceProvider for AtomicNonceProvider {
+    fn next_nonce(&self) -> BoxFuture<'_, Result<u64, Error>> {
+        Box::pin(future::ready(Ok(self.next())))
+    }
+}
+
+/// Strictly increasing nonce, persisted to a file
+///
+/// The file is locked while generating the nonce,
+/// so multiple processes using the same API key can share it.
+/// The file is accessed in a blocking thread, not to stall the runtime while another process holds the lock.
+#[cfg(feature = "file-nonce")]
+#[derive(Debug, Clone)]
+pub struct FileNonceProvider {
+    path: PathBuf,
+}
+
+#[cfg(feature = "file-nonce")]
+impl FileNonceProvider {
+    /// Construct a new provider, storing the last nonce in the file (created if missing)
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[cfg(feature = "file-nonce")]
+impl NonceProvider for FileNonceProvider {
+    fn next_nonce(&self) -> BoxFuture<'_, Result<u64, Error>> {
+        let path: PathBuf = self.path.clone();
+
+        Box::pin(async move {
+            task::spawn_blocking(move || next_file_nonce(&path))
+                .await
+                .map_err(|e| Error::Io(io::Error::other(e)))?
+        })
+    }
+}
+
+/// Lock the file, and replace the last nonce with the next one
+#[cfg(feature = "file-nonce")]
+fn next_file_nonce(path: &Path) -> Result<u64, Error> {
+    let mut file: File = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+
+    // Released when the file is closed
+    file.lock_exclusive()?;
+
+    let mut content: String = String::new();
+    file.read_to_string(&mut content)?;
+
+    // New files start from the current time, corrupted ones aren't reset:
+    // the next nonce could be lower than one already used
+    let content: &str = content.trim();
+    let last: u64 = match content.is_empty() {
+        true => 0,
+        false => content
+            .parse()
+            .map_err(|_| Error::InvalidNonceFile(path.to_path_buf()))?,
+    };
+    let next: u64 = now_millis().max(last.saturating_add(1));
+
+    file.seek(SeekFrom::Start(0))?;
+    file.set_len(0)?;
+    file.write_all(next.to_string().as_bytes())?;
+    file.sync_data()?;
+
+    Ok(next)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_nonce_strictly_increasing() {
+        let provider = AtomicNonceProvider::new();
+
+        let mut last: u64 = 0;
+        for _ in 0..10_000 {
+            let nonce = provider.next();
+            assert!(nonce > last);
+            last = nonce;
+        }
+
+        // No overflow
+        provider.last.store(u64::MAX, Ordering::Relaxed);
+        assert_eq!(provider.next(), u64::MAX);
+    }
+
+    #[test]
+    fn test_atomic_nonce_concurrent() {
+        let provider = Arc::new(AtomicNonceProvider::new());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let provider = provider.clone();
+                std::thread::spawn(move || {
+                    (0..1_000).map(|_| provider.next()).collect::<Vec<u64>>()
+                })
+            })
+            .collect();
+
+        let mut nonces: Vec<u64> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        nonces.sort_unstable();
+        nonces.dedup();
+        assert_eq!(nonces.len(), 4_000);
+    }
+
+    #[cfg(feature = "file-nonce")]
+    #[tokio::test]
+    async fn test_file_nonce() {
+        let path = std::env::temp_dir().join(format!("kraken-nonce-{}", now_millis()));
+
+        // Nonce in the future, set by another process
+        std::fs::write(&path, "99999999999999").unwrap();
+
+        let provider = FileNonceProvider::new(&path);
+        assert_eq!(provider.next_nonce().await.unwrap(), 100000000000000);
+        assert_eq!(provider.next_nonce().await.unwrap(), 100000000000001);
+
+        // No overflow
+        std::fs::write(&path, u64::MAX.to_string()).unwrap();
+        assert_eq!(provider.next_nonce().await.unwrap(), u64::MAX);
+
+        // Corrupted file: not reset
+        std::fs::write(&path, "corrupted").unwrap();
+        assert!(matches!(
+            provider.next_nonce().await,
+            Err(Error::InvalidNonceFile(..))
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "corrupted");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -7,9 +7,25 @@
 
 pub use ::url::*;
 
+pub use crate::amount::*;
+pub use crate::asset::*;
 pub use crate::auth::*;
+pub use crate::builder::*;
 pub use crate::client::*;
 pub use crate::constant::*;
+pub use crate::credentials::*;
 pub use crate::error::*;
+pub use crate::nonce::*;
+pub use crate::rate_limit::*;
+pub use crate::registry::*;
+pub use crate::request::*;
 pub use crate::response::*;
+pub use crate::retry::*;
+pub use crate::signer::*;
+#[cfg(feature = "websocket")]
+pub use crate::ws::book::*;
+#[cfg(feature = "websocket")]
+pub use crate::ws::message::*;
+#[cfg(feature = "websocket")]
+pub use crate::ws::*;
 pub use crate::*;
@@ -0,0 +1,164 @@
+//! Kraken API rate limit
+//!
+//! Kraken tracks the private calls with a counter per API key: every call increases it (by 2 for the ledger and
+//! trade history calls), and the counter decreases over time at a rate depending on the verification tier.
+//! When the counter exceeds the tier maximum, the calls fail with `EAPI:Rate limit exceeded`.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::{self, Instant};
+
+/// Account verification tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VerificationTier {
+    /// Starter
+    #[default]
+    Starter,
+    /// Intermediate
+    Intermediate,
+    /// Pro
+    Pro,
+}
+
+impl VerificationTier {
+    /// Max value of the API counter
+    pub fn max_counter(&self) -> f64 {
+        match self {
+            Self::Starter => 15.0,
+            Self::Intermediate | Self::Pro => 20.0,
+        }
+    }
+
+    /// Decrease of the API counter per second
+    pub fn decay_per_second(&self) -> f64 {
+        match self {
+            Self::Starter => 0.33,
+            Self::Intermediate => 0.5,
+            Self::Pro => 1.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Counter {
+    /// Current value, including the reserved costs of the delayed calls
+    value: f64,
+    /// Last update
+    updated_at: Instant,
+}
+
+/// Client-side model of the Kraken API counter
+///
+/// The calls are delayed until the counter has room for their cost.
+/// Clones share the same counter: use the same limiter for all the clients using the same API key.
+#[derive(Clone)]
+pub struct RateLimiter {
+    tier: VerificationTier,
+    counter: Arc<Mutex<Counter>>,
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("tier", &self.tier)
+            .finish()
+    }
+}
+
+impl RateLimiter {
+    /// Construct a new rate limiter for the verification tier.
+    pub fn new(tier: VerificationTier) -> Self {
+        Self {
+            tier,
+            counter: Arc::new(Mutex::new(Counter {
+                value: 0.0,
+                updated_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Get the verification tier
+    #[inline]
+    pub fn tier(&self) -> VerificationTier {
+        self.tier
+    }
+
+    /// Wait until the counter has room for a call of the given cost.
+    pub async fn acquire(&self, cost: u8) {
+        if cost == 0 {
+            return;
+        }
+
+        let delay: Duration = self.reserve(cost, Instant::now());
+
+        if !delay.is_zero() {
+            time::sleep(delay).await;
+        }
+    }
+
+    /// Mark the counter as full (i.e., after a rate limit error).
+    pub fn saturate(&self) {
+        let now: Instant = Instant::now();
+        let mut counter = self.counter.lock().unwrap_or_else(|e| e.into_inner());
+        self.decay(&mut counter, now);
+        counter.value = counter.value.max(self.tier.max_counter());
+    }
+
+    /// Add the cost to the counter, returning how long to wait before the call.
+    fn reserve(&self, cost: u8, now: Instant) -> Duration {
+        let mut counter = self.counter.lock().unwrap_or_else(|e| e.into_inner());
+        self.decay(&mut counter, now);
+
+        counter.value += f64::from(cost);
+
+        let excess: f64 = counter.value - self.tier.max_counter();
+        if excess > 0.0 {
+            Duration::from_secs_f64(excess / self.tier.decay_per_second())
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn decay(&self, counter: &mut Counter, now: Instant) {
+        let elapsed: f64 = now
+            .saturating_duration_since(counter.updated_at)
+            .as_secs_f64();
+        counter.value = (counter.value - elapsed * self.tier.decay_per_second()).max(0.0);
+        counter.updated_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let limiter = RateLimiter::new(VerificationTier::Starter);
+        let now = Instant::now();
+
+        // The first 15 points are free
+        for _ in 0..15 {
+            assert_eq!(limiter.reserve(1, now), Duration::ZERO);
+        }
+
+        // Then each call waits for the counter to decay
+        let delay = limiter.reserve(2, now);
+        assert!((delay.as_secs_f64() - 2.0 / 0.33).abs() < 1e-6);
+
+        // After the decay, there is room again
+        let later = now + Duration::from_secs(30);
+        assert_eq!(limiter.reserve(1, later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_saturate() {
+        let limiter = RateLimiter::new(VerificationTier::Pro);
+        limiter.saturate();
+
+        let delay = limiter.reserve(1, Instant::now());
+        assert!(delay > Duration::from_millis(900));
+    }
+}
@@ -0,0 +1,268 @@
+//! Asset registry
+//!
+//! Kraken names the assets inconsistently: i.e., bitcoin is `XXBT` in the balances, `XBT` in the pair
+//! alternate names and `BTC` everywhere else. The registry resolves all of them to the Kraken asset name.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::asset::AssetFamily;
+use crate::response::{AssetInfo, AssetPairInfo};
+
+/// Common names of the assets named differently by Kraken (Kraken alternate name, common name)
+const COMMON_NAMES: &[(&str, &str)] = &[("XBT", "BTC"), ("XDG", "DOGE")];
+
+/// Registry of the assets and asset pairs, built from the `Assets` and `AssetPairs` endpoints
+#[derive(Debug, Clone, Default)]
+pub struct AssetRegistry {
+    /// Assets, by Kraken name
+    assets: HashMap<String, AssetInfo>,
+    /// Kraken asset names, by alias (uppercase)
+    asset_aliases: HashMap<String, String>,
+    /// Pairs, by Kraken name
+    pairs: HashMap<String, AssetPairInfo>,
+    /// Kraken pair names, by alias (uppercase)
+    pair_aliases: HashMap<String, String>,
+}
+
+impl AssetRegistry {
+    /// Construct a new registry, from the `Assets` and `AssetPairs` responses
+    pub fn new(assets: HashMap<String, AssetInfo>, pairs: HashMap<String, AssetPairInfo>) -> Self {
+        let mut registry: Self = Self {
+            assets,
+            asset_aliases: HashMap::new(),
+            pairs,
+            pair_aliases: HashMap::new(),
+        };
+
+        // Sort the names, for deterministic aliases
+        let mut asset_names: Vec<&String> = registry.assets.keys().collect();
+        asset_names.sort();
+
+        // The Kraken names take precedence over the aliases
+        for name in asset_names.iter() {
+            registry
+                .asset_aliases
+                .insert(name.to_ascii_uppercase(), name.to_string());
+        }
+
+        for name in asset_names.iter() {
+            let info: &AssetInfo = &registry.assets[*name];
+            for alias in [info.altname.clone(), common_name(&info.altname)] {
+                registry
+                    .asset_aliases
+                    .entry(alias.to_ascii_uppercase())
+                    .or_insert_with(|| name.to_string());
+            }
+        }
+
+        let mut pair_names: Vec<&String> = registry.pairs.keys().collect();
+        pair_names.sort();
+
+        for name in pair_names.iter() {
+            registry
+                .pair_aliases
+                .insert(name.to_ascii_uppercase(), name.to_string());
+        }
+
+        for name in pair_names.iter() {
+            let info: &AssetPairInfo = &registry.pairs[*name];
+
+            let mut aliases: Vec<String> = vec![info.altname.clone()];
+
+            if let Some(wsname) = &info.wsname {
+                aliases.push(wsname.clone());
+                aliases.push(wsname.replace('/', ""));
+            }
+
+            // Common names (i.e., `BTC/USD`)
+            if let (Some(base), Some(quote)) = (
+                registry.assets.get(&info.base),
+                registry.assets.get(&info.quote),
+            ) {
+                let base: String = common_name(&base.altname);
+                let quote: String = common_name(&quote.altname);
+                aliases.push(format!("{base}/{quote}"));
+                aliases.push(format!("{base}{quote}"));
+            }
+
+            for alias in aliases.into_iter() {
+                registry
+                    .pair_aliases
+                    .entry(alias.to_ascii_uppercase())
+                    .or_insert_with(|| name.to_string());
+            }
+        }
+
+        registry
+    }
+
+    /// Get the Kraken name of the asset (i.e., `XXBT` for `BTC`, `XBT` or `XXBT`)
+    pub fn normalize_asset(&self, asset: &str) -> Option<&str> {
+        self.asset_aliases
+            .get(&asset.to_ascii_uppercase())
+            .map(String::as_str)
+    }
+
+    /// Get the asset info, by any of its names
+    pub fn asset(&self, asset: &str) -> Option<&AssetInfo> {
+        self.normalize_asset(asset)
+            .and_then(|name| self.assets.get(name))
+    }
+
+    /// Get the common name of the asset (i.e., `BTC` for `XXBT`, `USD` for `ZUSD`)
+    pub fn common_name(&self, asset: &str) -> Option<String> {
+        self.asset(asset).map(|info| common_name(&info.altname))
+    }
+
+    /// Iterate the assets, by Kraken name
+    pub fn assets(&self) -> impl Iterator<Item = (&str, &AssetInfo)> {
+        self.assets.iter().map(|(name, info)| (name.as_str(), info))
+    }
+
+    /// Get the Kraken name of the pair (i.e., `XXBTZUSD` for `BTC/USD`, `XBTUSD` or `XBT/USD`)
+    pub fn normalize_pair(&self, pair: &str) -> Option<&str> {
+        self.pair_aliases
+            .get(&pair.to_ascii_uppercase())
+            .map(String::as_str)
+    }
+
+    /// Get the pair info (decimals, lot size, minimum order, ...), by any of its names
+    pub fn pair(&self, pair: &str) -> Option<&AssetPairInfo> {
+        self.normalize_pair(pair)
+            .and_then(|name| self.pairs.get(name))
+    }
+
+    /// Get the Kraken names of the base and quote assets of the pair
+    pub fn resolve_pair(&self, pair: &str) -> Option<(&str, &str)> {
+        self.pair(pair)
+            .map(|info| (info.base.as_str(), info.quote.as_str()))
+    }
+
+    /// Get the family of the asset, with all the variants listed by Kraken (i.e., `XXBT`, `XBT.B`, `XBT.F` for `BTC`)
+    pub fn family(&self, asset: &str) -> Option<AssetFamily> {
+        let info: &AssetInfo = self.asset(asset)?;
+        let ticker: &str = base_name(&info.altname);
+
+        let tickers: HashSet<String> = self
+            .assets
+            .iter()
+            .filter(|(_, info)| base_name(&info.altname) == ticker)
+            .flat_map(|(name, info)| [name.clone(), info.altname.clone()])
+            .collect();
+
+        Some(AssetFamily::with_tickers(ticker.to_string(), tickers))
+    }
+
+    /// Iterate the pairs, by Kraken name
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &AssetPairInfo)> {
+        self.pairs.iter().map(|(name, info)| (name.as_str(), info))
+    }
+}
+
+/// Alternate name without the variant suffix (i.e., `XBT` for `XBT.F`)
+fn base_name(altname: &str) -> &str {
+    altname.split_once('.').map_or(altname, |(base, _)| base)
+}
+
+/// Common name of a Kraken alternate name, keeping the suffix (i.e., `BTC.F` for `XBT.F`)
+fn common_name(altname: &str) -> String {
+    let (base, suffix) = match altname.split_once('.') {
+        Some((base, suffix)) => (base, Some(suffix)),
+        None => (altname, None),
+    };
+
+    let base: &str = COMMON_NAMES
+        .iter()
+        .find(|(kraken, _)| *kraken == base)
+        .map_or(base, |(_, common)| common);
+
+    match suffix {
+        Some(suffix) => format!("{base}.{suffix}"),
+        None => base.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> AssetRegistry {
+        let assets = r#"{
+            "XXBT": {"aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5, "status": "enabled"},
+            "XBT.F": {"aclass": "currency", "altname": "XBT.F", "decimals": 10, "display_decimals": 5, "status": "enabled"},
+            "XETH": {"aclass": "currency", "altname": "ETH", "decimals": 10, "display_decimals": 5, "status": "enabled"},
+            "ZUSD": {"aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2, "status": "enabled"},
+            "USDC": {"aclass": "currency", "altname": "USDC", "decimals": 8, "display_decimals": 4, "status": "enabled"}
+        }"#;
+        let pairs = r#"{
+            "XXBTZUSD": {"altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency", "base": "XXBT", "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "cost_decimals": 5, "pair_decimals": 1, "lot_decimals": 8, "lot_multiplier": 1, "ordermin": "0.0001", "costmin": "0.5", "tick_size": "0.1", "status": "online"},
+            "XETHXXBT": {"altname": "ETHXBT", "wsname": "ETH/XBT", "aclass_base": "currency", "base": "XETH", "aclass_quote": "currency", "quote": "XXBT", "lot": "unit", "cost_decimals": 10, "pair_decimals": 5, "lot_decimals": 8, "lot_multiplier": 1, "ordermin": "0.002", "status": "online"},
+            "USDCUSD": {"altname": "USDCUSD", "wsname": "USDC/USD", "aclass_base": "currency", "base": "USDC", "aclass_quote": "currency", "quote": "ZUSD", "lot": "unit", "cost_decimals": 8, "pair_decimals": 4, "lot_decimals": 8, "lot_multiplier": 1, "ordermin": "5", "status": "online"}
+        }"#;
+
+        AssetRegistry::new(
+            serde_json::from_str(assets).unwrap(),
+            serde_json::from_str(pairs).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_normalize_asset() {
+        let registry = registry();
+
+        for alias in ["XXBT", "XBT", "BTC", "btc"] {
+            assert_eq!(registry.normalize_asset(alias), Some("XXBT"));
+        }
+        for alias in ["ZUSD", "USD"] {
+            assert_eq!(registry.normalize_asset(alias), Some("ZUSD"));
+        }
+        assert_eq!(registry.normalize_asset("BTC.F"), Some("XBT.F"));
+        assert_eq!(registry.normalize_asset("USDC"), Some("USDC"));
+        assert_eq!(registry.normalize_asset("SOL"), None);
+
+        assert_eq!(registry.common_name("XXBT").as_deref(), Some("BTC"));
+        assert_eq!(registry.common_name("ZUSD").as_deref(), Some("USD"));
+        assert_eq!(registry.asset("BTC").unwrap().decimals, 10);
+    }
+
+    #[test]
+    fn test_resolve_pair() {
+        let registry = registry();
+
+        for alias in [
+            "XXBTZUSD", "XBTUSD", "XBT/USD", "BTC/USD", "BTCUSD", "btc/usd",
+        ] {
+            assert_eq!(registry.normalize_pair(alias), Some("XXBTZUSD"));
+            assert_eq!(registry.resolve_pair(alias), Some(("XXBT", "ZUSD")));
+        }
+
+        assert_eq!(registry.resolve_pair("ETH/BTC"), Some(("XETH", "XXBT")));
+        assert_eq!(registry.resolve_pair("USDCUSD"), Some(("USDC", "ZUSD")));
+        assert_eq!(registry.resolve_pair("SOL/USD"), None);
+
+        let pair = registry.pair("BTC/USD").unwrap();
+        assert_eq!(pair.lot_decimals, 8);
+        assert_eq!(pair.pair_decimals, 1);
+        assert!(pair.ordermin.is_some());
+    }
+
+    #[test]
+    fn test_family() {
+        let registry = registry();
+
+        let family = registry.family("BTC").unwrap();
+        assert_eq!(family.ticker(), "XBT");
+        for ticker in ["XXBT", "XBT", "XBT.F"] {
+            assert!(family.contains(ticker));
+        }
+        assert!(!family.contains("XETH"));
+
+        assert!(family.matches_pair(&registry, "XXBTZUSD"));
+        assert!(family.matches_pair(&registry, "XETHXXBT"));
+        assert!(!family.matches_pair(&registry, "USDCUSD"));
+
+        // Delisted pair
+        assert!(family.matches_pair(&registry, "XXBTZGBP"));
+        assert!(!family.matches_pair(&registry, "WBTCUSD"));
+    }
+}
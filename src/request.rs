@@ -1,4 +1,11 @@
-use serde::Serialize;
+//! Kraken API requests
+
+use serde::{Deserialize, Serialize, Serializer};
+use zeroize::Zeroizing;
+
+use crate::amount::Amount;
+use crate::error::Error;
+use crate::response::TrateType;
 
 /// Empty json object (used as arguments for some APIs)
 #[derive(Debug, Serialize)]
@@ -7,6 +14,8 @@ pub(crate) struct Empty {}
 #[derive(Debug, Serialize)]
 pub(crate) struct KrakenRequestBody<'a> {
     pub(crate) nonce: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) otp: Option<Zeroizing<String>>,
     #[serde(flatten)]
     pub(crate) request: Request<'a>,
 }
@@ -15,36 +24,1196 @@ pub(crate) struct KrakenRequestBody<'a> {
 #[serde(untagged)]
 pub(crate) enum Request<'a> {
     Empty(Empty),
+    Ticker(GetTicker),
+    Assets(GetAssets),
+    AssetPairs(GetAssetPairs),
+    Ohlc(GetOhlc<'a>),
+    Depth(GetDepth<'a>),
+    Trades(GetRecentTrades<'a>),
+    Spread(GetSpread<'a>),
+    TradeBalance(GetTradeBalance<'a>),
+    TradeVolume(GetTradeVolume),
+    OpenPositions(GetOpenPositions),
     DepositStatus(DepositStatus<'a>),
     WithdrawStatus(WithdrawStatus<'a>),
     TradesHistory(GetTradesHistory<'a>),
+    Ledgers(GetLedgers<'a>),
+    QueryLedgers(QueryLedgers),
+    AddOrder(&'a AddOrder),
+    EditOrder(&'a EditOrder),
+    CancelOrder(CancelOrder<'a>),
+    CancelAllOrdersAfter(CancelAllOrdersAfter),
+    OpenOrders(GetOpenOrders),
+    ClosedOrders(GetClosedOrders<'a>),
+    QueryOrders(QueryOrders),
+    DepositMethods(GetDepositMethods<'a>),
+    DepositAddresses(GetDepositAddresses<'a>),
+    WithdrawMethods(GetWithdrawMethods<'a>),
+    WithdrawAddresses(GetWithdrawAddresses<'a>),
+    WithdrawInfo(GetWithdrawInfo<'a>),
+    Withdraw(&'a Withdraw),
+    WithdrawCancel(WithdrawCancel<'a>),
+    EarnStrategies(&'a EarnStrategiesOptions),
+    EarnAllocate(EarnAllocate<'a>),
+    EarnStatus(EarnStatus<'a>),
+    EarnAllocations(&'a EarnAllocationsOptions),
+}
+
+/// OHLC interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OhlcInterval {
+    /// 1 minute
+    OneMinute,
+    /// 5 minutes
+    FiveMinutes,
+    /// 15 minutes
+    FifteenMinutes,
+    /// 30 minutes
+    ThirtyMinutes,
+    /// 1 hour
+    OneHour,
+    /// 4 hours
+    FourHours,
+    /// 1 day
+    OneDay,
+    /// 1 week
+    OneWeek,
+    /// 15 days
+    FifteenDays,
+}
+
+impl OhlcInterval {
+    /// Interval in minutes
+    pub fn as_minutes(&self) -> u32 {
+        match self {
+            Self::OneMinute => 1,
+            Self::FiveMinutes => 5,
+            Self::FifteenMinutes => 15,
+            Self::ThirtyMinutes => 30,
+            Self::OneHour => 60,
+            Self::FourHours => 240,
+            Self::OneDay => 1440,
+            Self::OneWeek => 10080,
+            Self::FifteenDays => 21600,
+        }
+    }
+}
+
+impl Serialize for OhlcInterval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u32(self.as_minutes())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetTicker {
+    /// Comma delimited list of asset pairs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pair: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetAssets {
+    /// Comma delimited list of assets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) asset: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetAssetPairs {
+    /// Comma delimited list of asset pairs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pair: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetOhlc<'a> {
+    pub(crate) pair: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) interval: Option<OhlcInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) since: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetDepth<'a> {
+    pub(crate) pair: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) count: Option<u16>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetRecentTrades<'a> {
+    pub(crate) pair: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) since: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) count: Option<u16>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetSpread<'a> {
+    pub(crate) pair: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) since: Option<u64>,
+}
+
+/// Start/end bound of a history query
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum Bound {
+    /// Unix timestamp
+    Timestamp(u64),
+    /// Reference ID (i.e., ledger, trade or transaction ID)
+    RefId(String),
+}
+
+impl From<u64> for Bound {
+    fn from(timestamp: u64) -> Self {
+        Self::Timestamp(timestamp)
+    }
+}
+
+impl From<&str> for Bound {
+    fn from(refid: &str) -> Self {
+        Self::RefId(refid.to_string())
+    }
+}
+
+impl From<String> for Bound {
+    fn from(refid: String) -> Self {
+        Self::RefId(refid)
+    }
+}
+
+/// Cursor of a funding history query
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    /// Enable the paginated response, starting from the first page
+    Start,
+    /// Continue from a `next_cursor` returned by a previous page
+    Next(String),
+}
+
+impl Serialize for Cursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Start => serializer.serialize_bool(true),
+            Self::Next(cursor) => serializer.serialize_str(cursor),
+        }
+    }
+}
+
+/// Trade type filter of the trade history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+pub enum TradeType {
+    /// All trades
+    #[default]
+    #[serde(rename = "all")]
+    All,
+    /// Trades that opened or closed a margin position
+    #[serde(rename = "any position")]
+    AnyPosition,
+    /// Trades that closed a margin position
+    #[serde(rename = "closed position")]
+    ClosedPosition,
+    /// Trades that closed a margin position, partially or fully
+    #[serde(rename = "closing position")]
+    ClosingPosition,
+    /// Trades not related to a margin position
+    #[serde(rename = "no position")]
+    NoPosition,
+}
+
+/// History query options
+///
+/// The `start` and `end` bounds apply to all the history queries, the trade type only to the trade history,
+/// the other options only to the funding (deposit/withdraw) history.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryOptions {
+    #[serde(skip)]
+    pub(crate) trade_type: TradeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) start: Option<Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) aclass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cursor: Option<Cursor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) limit: Option<u64>,
+}
+
+impl QueryOptions {
+    /// New empty options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starting unix timestamp or reference ID (exclusive)
+    #[inline]
+    pub fn start<B>(mut self, start: B) -> Self
+    where
+        B: Into<Bound>,
+    {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Ending unix timestamp or reference ID (inclusive)
+    #[inline]
+    pub fn end<B>(mut self, end: B) -> Self
+    where
+        B: Into<Bound>,
+    {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Filter the trade history by trade type (all trades by default)
+    #[inline]
+    pub fn trade_type(mut self, trade_type: TradeType) -> Self {
+        self.trade_type = trade_type;
+        self
+    }
+
+    /// Name of the funding method (i.e., `Bitcoin Lightning`)
+    #[inline]
+    pub fn method<S>(mut self, method: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// Asset class (i.e., `currency`)
+    #[inline]
+    pub fn asset_class<S>(mut self, aclass: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.aclass = Some(aclass.into());
+        self
+    }
+
+    /// Cursor for the paginated funding history
+    #[inline]
+    pub fn cursor(mut self, cursor: Cursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Number of results to include per page
+    #[inline]
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Ledger entry type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerType {
+    /// Trade
+    Trade,
+    /// Deposit
+    Deposit,
+    /// Withdrawal
+    Withdrawal,
+    /// Transfer
+    Transfer,
+    /// Margin
+    Margin,
+    /// Adjustment
+    Adjustment,
+    /// Rollover
+    Rollover,
+    /// Spend
+    Spend,
+    /// Receive
+    Receive,
+    /// Settled
+    Settled,
+    /// Credit
+    Credit,
+    /// Staking
+    Staking,
+    /// Reward
+    Reward,
+    /// Dividend
+    Dividend,
+    /// Sale
+    Sale,
+    /// Conversion
+    Conversion,
+    /// Earn
+    Earn,
+    /// Other type, not known by this library (ignored as filter: all types are returned)
+    #[serde(other)]
+    Other,
+}
+
+/// Skip the unknown ledger type: Kraken rejects it
+fn is_any_ledger_type(r#type: &Option<LedgerType>) -> bool {
+    matches!(r#type, None | Some(LedgerType::Other))
+}
+
+/// Ledgers query options
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LedgersOptions {
+    /// Comma delimited list of assets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) aclass: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "is_any_ledger_type")]
+    pub(crate) r#type: Option<LedgerType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) start: Option<Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<Bound>,
+}
+
+impl LedgersOptions {
+    /// New empty options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by assets (all assets by default)
+    #[inline]
+    pub fn assets<I, S>(mut self, assets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let assets: Vec<S> = assets.into_iter().collect();
+        let assets: Vec<&str> = assets.iter().map(|a| a.as_ref()).collect();
+        self.asset = Some(assets.join(","));
+        self
+    }
+
+    /// Filter by asset class (i.e., `currency`)
+    #[inline]
+    pub fn asset_class<S>(mut self, aclass: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.aclass = Some(aclass.into());
+        self
+    }
+
+    /// Filter by ledger entry type (all types by default, or with [`LedgerType::Other`])
+    #[inline]
+    pub fn ledger_type(mut self, r#type: LedgerType) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    /// Starting unix timestamp or ledger ID (exclusive)
+    #[inline]
+    pub fn start<B>(mut self, start: B) -> Self
+    where
+        B: Into<Bound>,
+    {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Ending unix timestamp or ledger ID (inclusive)
+    #[inline]
+    pub fn end<B>(mut self, end: B) -> Self
+    where
+        B: Into<Bound>,
+    {
+        self.end = Some(end.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetLedgers<'a> {
+    #[serde(flatten)]
+    pub(crate) options: &'a LedgersOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ofs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct QueryLedgers {
+    /// Comma delimited list of ledger IDs
+    pub(crate) id: String,
+}
+
+/// Order type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrderType {
+    /// Market
+    Market,
+    /// Limit
+    Limit,
+    /// Stop loss
+    StopLoss,
+    /// Take profit
+    TakeProfit,
+    /// Stop loss limit
+    StopLossLimit,
+    /// Take profit limit
+    TakeProfitLimit,
+    /// Iceberg
+    Iceberg,
+    /// Trailing stop
+    TrailingStop,
+    /// Trailing stop limit
+    TrailingStopLimit,
+    /// Settle position
+    SettlePosition,
+    /// Other type, not known by this library (rejected as order type)
+    #[serde(other)]
+    Other,
+}
+
+/// Order flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderFlag {
+    /// Post-only order (available when order type is limit)
+    Post,
+    /// Prefer fee in base currency
+    Fcib,
+    /// Prefer fee in quote currency
+    Fciq,
+    /// Disable market price protection for market orders
+    Nompp,
+    /// Order volume expressed in quote currency
+    Viqc,
+}
+
+impl OrderFlag {
+    /// Get the flag as str
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Post => "post",
+            Self::Fcib => "fcib",
+            Self::Fciq => "fciq",
+            Self::Nompp => "nompp",
+            Self::Viqc => "viqc",
+        }
+    }
+}
+
+/// Time in force
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TimeInForce {
+    /// Good till cancelled
+    Gtc,
+    /// Immediate or cancel
+    Ioc,
+    /// Good till date (requires an expiration time, see [`AddOrder::expire_time`])
+    Gtd,
+}
+
+/// Scheduled start or expiration time of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderTime {
+    /// Now (as expiration time: no expiration)
+    Now,
+    /// Seconds from now
+    After(u64),
+    /// Unix timestamp
+    At(u64),
+}
+
+impl Serialize for OrderTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Now => serializer.serialize_str("0"),
+            Self::After(secs) => serializer.collect_str(&format_args!("+{secs}")),
+            Self::At(timestamp) => serializer.collect_str(timestamp),
+        }
+    }
+}
+
+/// Conditional close order, placed when the order is filled
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseOrder {
+    #[serde(rename = "ordertype")]
+    order_type: OrderType,
+    price: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price2: Option<Amount>,
+}
+
+impl CloseOrder {
+    /// Close order of the type, at `price` (limit price or trigger price)
+    pub fn new(order_type: OrderType, price: Amount) -> Self {
+        Self {
+            order_type,
+            price,
+            price2: None,
+        }
+    }
+
+    /// Set the secondary price (limit price of stop loss/take profit limit orders)
+    #[inline]
+    pub fn price2(mut self, price2: Amount) -> Self {
+        self.price2 = Some(price2);
+        self
+    }
+}
+
+fn serialize_order_flags<S>(flags: &[OrderFlag], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let flags: Vec<&str> = flags.iter().map(|f| f.as_str()).collect();
+    serializer.serialize_str(&flags.join(","))
+}
+
+/// Add order request
+#[derive(Debug, Clone, Serialize)]
+pub struct AddOrder {
+    #[serde(rename = "ordertype")]
+    order_type: OrderType,
+    #[serde(rename = "type")]
+    side: TrateType,
+    volume: Amount,
+    pair: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price2: Option<Amount>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_order_flags"
+    )]
+    oflags: Vec<OrderFlag>,
+    #[serde(rename = "timeinforce", skip_serializing_if = "Option::is_none")]
+    time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    starttm: Option<OrderTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiretm: Option<OrderTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close: Option<CloseOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    leverage: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    reduce_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    userref: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    validate: bool,
+}
+
+impl AddOrder {
+    fn new<S>(order_type: OrderType, pair: S, side: TrateType, volume: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            order_type,
+            side,
+            volume,
+            pair: pair.into(),
+            price: None,
+            price2: None,
+            oflags: Vec::new(),
+            time_in_force: None,
+            starttm: None,
+            expiretm: None,
+            close: None,
+            leverage: None,
+            reduce_only: false,
+            userref: None,
+            cl_ord_id: None,
+            validate: false,
+        }
+    }
+
+    /// Check the order before sending it
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        if self.time_in_force == Some(TimeInForce::Gtd)
+            && matches!(self.expiretm, None | Some(OrderTime::Now))
+        {
+            return Err(Error::InvalidOrder(String::from(
+                "good till date orders require an expiration time",
+            )));
+        }
+
+        if self
+            .close
+            .as_ref()
+            .is_some_and(|close| close.order_type == OrderType::Other)
+        {
+            return Err(Error::InvalidOrder(String::from(
+                "unknown close order type",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Market order
+    pub fn market<S>(pair: S, side: TrateType, volume: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(OrderType::Market, pair, side, volume)
+    }
+
+    /// Limit order
+    pub fn limit<S>(pair: S, side: TrateType, volume: Amount, price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::Limit, pair, side, volume);
+        order.price = Some(price);
+        order
+    }
+
+    /// Stop loss order, triggered at `trigger_price`
+    pub fn stop_loss<S>(pair: S, side: TrateType, volume: Amount, trigger_price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::StopLoss, pair, side, volume);
+        order.price = Some(trigger_price);
+        order
+    }
+
+    /// Take profit order, triggered at `trigger_price`
+    pub fn take_profit<S>(pair: S, side: TrateType, volume: Amount, trigger_price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::TakeProfit, pair, side, volume);
+        order.price = Some(trigger_price);
+        order
+    }
+
+    /// Stop loss limit order, placing a limit order at `limit_price` when `trigger_price` is reached
+    pub fn stop_loss_limit<S>(
+        pair: S,
+        side: TrateType,
+        volume: Amount,
+        trigger_price: Amount,
+        limit_price: Amount,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::StopLossLimit, pair, side, volume);
+        order.price = Some(trigger_price);
+        order.price2 = Some(limit_price);
+        order
+    }
+
+    /// Take profit limit order, placing a limit order at `limit_price` when `trigger_price` is reached
+    pub fn take_profit_limit<S>(
+        pair: S,
+        side: TrateType,
+        volume: Amount,
+        trigger_price: Amount,
+        limit_price: Amount,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::TakeProfitLimit, pair, side, volume);
+        order.price = Some(trigger_price);
+        order.price2 = Some(limit_price);
+        order
+    }
+
+    /// Add an order flag
+    #[inline]
+    pub fn flag(mut self, flag: OrderFlag) -> Self {
+        if !self.oflags.contains(&flag) {
+            self.oflags.push(flag);
+        }
+        self
+    }
+
+    /// Set the time in force
+    #[inline]
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Set the scheduled start time (now by default)
+    #[inline]
+    pub fn start_time(mut self, start_time: OrderTime) -> Self {
+        self.starttm = Some(start_time);
+        self
+    }
+
+    /// Set the expiration time (no expiration by default, required by [`TimeInForce::Gtd`])
+    #[inline]
+    pub fn expire_time(mut self, expire_time: OrderTime) -> Self {
+        self.expiretm = Some(expire_time);
+        self
+    }
+
+    /// Set the conditional close order, placed when the order is filled
+    #[inline]
+    pub fn close(mut self, close: CloseOrder) -> Self {
+        self.close = Some(close);
+        self
+    }
+
+    /// Set the leverage of a margin order (i.e., `5` for 5:1)
+    #[inline]
+    pub fn leverage(mut self, leverage: u8) -> Self {
+        self.leverage = Some(leverage.to_string());
+        self
+    }
+
+    /// Only reduce an existing margin position, without opening a new one
+    #[inline]
+    pub fn reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    /// Set a user reference ID
+    #[inline]
+    pub fn userref(mut self, userref: i32) -> Self {
+        self.userref = Some(userref);
+        self
+    }
+
+    /// Set a client order ID
+    #[inline]
+    pub fn client_order_id<S>(mut self, id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.cl_ord_id = Some(id.into());
+        self
+    }
+
+    /// Validate inputs only, without submitting the order
+    #[inline]
+    pub fn validate(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+}
+
+/// Edit order request
+#[derive(Debug, Clone, Serialize)]
+pub struct EditOrder {
+    txid: String,
+    pair: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price2: Option<Amount>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_order_flags"
+    )]
+    oflags: Vec<OrderFlag>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    userref: Option<i32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    validate: bool,
+}
+
+impl EditOrder {
+    /// Edit the order with the given transaction ID
+    pub fn new<T, P>(txid: T, pair: P) -> Self
+    where
+        T: Into<String>,
+        P: Into<String>,
+    {
+        Self {
+            txid: txid.into(),
+            pair: pair.into(),
+            volume: None,
+            price: None,
+            price2: None,
+            oflags: Vec::new(),
+            userref: None,
+            validate: false,
+        }
+    }
+
+    /// Set the new volume
+    #[inline]
+    pub fn volume(mut self, volume: Amount) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    /// Set the new price (limit price or trigger price)
+    #[inline]
+    pub fn price(mut self, price: Amount) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Set the new secondary price (limit price of stop loss/take profit limit orders)
+    #[inline]
+    pub fn price2(mut self, price2: Amount) -> Self {
+        self.price2 = Some(price2);
+        self
+    }
+
+    /// Add an order flag
+    #[inline]
+    pub fn flag(mut self, flag: OrderFlag) -> Self {
+        if !self.oflags.contains(&flag) {
+            self.oflags.push(flag);
+        }
+        self
+    }
+
+    /// Set a user reference ID
+    #[inline]
+    pub fn userref(mut self, userref: i32) -> Self {
+        self.userref = Some(userref);
+        self
+    }
+
+    /// Validate inputs only, without submitting the edit
+    #[inline]
+    pub fn validate(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CancelOrder<'a> {
+    pub(crate) txid: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CancelAllOrdersAfter {
+    pub(crate) timeout: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetOpenOrders {
+    pub(crate) trades: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetClosedOrders<'a> {
+    pub(crate) trades: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) start: Option<&'a Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<&'a Bound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ofs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetTradeBalance<'a> {
+    /// Base asset used to determine balance (default: `ZUSD`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) asset: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetTradeVolume {
+    /// Comma delimited list of asset pairs to get the fee info for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pair: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetOpenPositions {
+    /// Comma delimited list of transaction IDs to restrict output to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) txid: Option<String>,
+    /// Whether to include the profit/loss calculations
+    pub(crate) docalcs: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct QueryOrders {
+    pub(crate) trades: bool,
+    /// Comma delimited list of transaction IDs
+    pub(crate) txid: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetDepositMethods<'a> {
+    pub(crate) asset: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetDepositAddresses<'a> {
+    pub(crate) asset: &'a str,
+    pub(crate) method: &'a str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) new: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) amount: Option<&'a Amount>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetWithdrawMethods<'a> {
+    pub(crate) asset: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetWithdrawAddresses<'a> {
+    pub(crate) asset: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) method: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GetWithdrawInfo<'a> {
+    pub(crate) asset: &'a str,
+    pub(crate) key: &'a str,
+    pub(crate) amount: &'a Amount,
+}
+
+/// Withdraw request
+#[derive(Debug, Clone, Serialize)]
+pub struct Withdraw {
+    asset: String,
+    key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_fee: Option<Amount>,
+}
+
+impl Withdraw {
+    /// Withdraw the amount of the asset to the whitelisted withdrawal key
+    pub fn new<A, K>(asset: A, key: K, amount: Amount) -> Self
+    where
+        A: Into<String>,
+        K: Into<String>,
+    {
+        Self {
+            asset: asset.into(),
+            key: key.into(),
+            address: None,
+            amount,
+            max_fee: None,
+        }
+    }
+
+    /// Address to withdraw to (must match one of the addresses of the withdrawal key)
+    #[inline]
+    pub fn address<S>(mut self, address: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Fail the withdrawal if the processed fee is higher than `max_fee`
+    #[inline]
+    pub fn max_fee(mut self, max_fee: Amount) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WithdrawCancel<'a> {
+    pub(crate) asset: &'a str,
+    pub(crate) refid: &'a str,
 }
 
 #[derive(Debug, Serialize)]
 pub(crate) struct DepositStatus<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) asset: Option<&'a str>,
+    #[serde(flatten)]
+    pub(crate) options: &'a QueryOptions,
 }
 
 #[derive(Debug, Serialize)]
 pub(crate) struct WithdrawStatus<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) asset: Option<&'a str>,
+    #[serde(flatten)]
+    pub(crate) options: &'a QueryOptions,
 }
 
 #[derive(Debug, Serialize)]
 pub(crate) struct GetTradesHistory<'a> {
     #[serde(rename = "type")]
-    pub r#type: &'a str,
+    pub r#type: TradeType,
     pub trades: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<u64>,
+    pub start: Option<&'a Bound>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end: Option<u64>,
+    pub end: Option<&'a Bound>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ofs: Option<u64>,
 }
 
+/// Earn strategy lock type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockType {
+    /// Flexible: allocated and deallocated instantly, rewards paid on a schedule
+    Flex,
+    /// Bonded: bonding and unbonding periods apply
+    Bonded,
+    /// Timed: locked for a fixed period
+    Timed,
+    /// Instant: rewards paid instantly
+    Instant,
+}
+
+/// Earn strategies query options
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EarnStrategiesOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) asset: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) lock_type: Vec<LockType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ascending: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) limit: Option<u16>,
+}
+
+impl EarnStrategiesOptions {
+    /// New empty options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by asset (all assets by default)
+    #[inline]
+    pub fn asset<S>(mut self, asset: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.asset = Some(asset.into());
+        self
+    }
+
+    /// Filter by lock types (all types by default)
+    #[inline]
+    pub fn lock_types<I>(mut self, lock_types: I) -> Self
+    where
+        I: IntoIterator<Item = LockType>,
+    {
+        self.lock_type = lock_types.into_iter().collect();
+        self
+    }
+
+    /// Sort the strategies by ascending ID (descending by default)
+    #[inline]
+    pub fn ascending(mut self, ascending: bool) -> Self {
+        self.ascending = Some(ascending);
+        self
+    }
+
+    /// Page cursor, as returned in [`EarnStrategies::next_cursor`](crate::response::EarnStrategies::next_cursor)
+    #[inline]
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Maximum number of strategies per page
+    #[inline]
+    pub fn limit(mut self, limit: u16) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Earn allocations query options
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EarnAllocationsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ascending: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) converted_asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hide_zero_allocations: Option<bool>,
+}
+
+impl EarnAllocationsOptions {
+    /// New empty options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sort the allocations by ascending strategy ID (descending by default)
+    #[inline]
+    pub fn ascending(mut self, ascending: bool) -> Self {
+        self.ascending = Some(ascending);
+        self
+    }
+
+    /// Asset to convert the amounts to (default: `USD`)
+    #[inline]
+    pub fn converted_asset<S>(mut self, asset: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.converted_asset = Some(asset.into());
+        self
+    }
+
+    /// Omit the strategies with no allocations
+    #[inline]
+    pub fn hide_zero_allocations(mut self, hide: bool) -> Self {
+        self.hide_zero_allocations = Some(hide);
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EarnAllocate<'a> {
+    pub(crate) amount: &'a Amount,
+    pub(crate) strategy_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EarnStatus<'a> {
+    pub(crate) strategy_id: &'a str,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,19 +1222,235 @@ mod tests {
     fn test_empty_serialize() {
         let status = KrakenRequestBody {
             nonce: 1234567890,
+            otp: None,
             request: Request::Empty(Empty {}),
         };
         let json = serde_json::to_string(&status).unwrap();
         assert_eq!(json, r#"{"nonce":1234567890}"#);
+
+        let status = KrakenRequestBody {
+            nonce: 1234567890,
+            otp: Some(Zeroizing::new(String::from("287082"))),
+            request: Request::Empty(Empty {}),
+        };
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(json, r#"{"nonce":1234567890,"otp":"287082"}"#);
+    }
+
+    #[test]
+    fn test_earn_serialize() {
+        let options = EarnStrategiesOptions::new()
+            .asset("DOT")
+            .lock_types([LockType::Flex, LockType::Bonded])
+            .limit(10);
+        let json = serde_json::to_string(&Request::EarnStrategies(&options)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"asset":"DOT","lock_type":["flex","bonded"],"limit":10}"#
+        );
+
+        let amount: Amount = "1.5".parse().unwrap();
+        let allocate = Request::EarnAllocate(EarnAllocate {
+            amount: &amount,
+            strategy_id: "ESRFUO3-Q62XD-WIOIL7",
+        });
+        let json = serde_json::to_string(&allocate).unwrap();
+        assert_eq!(
+            json,
+            r#"{"amount":"1.5","strategy_id":"ESRFUO3-Q62XD-WIOIL7"}"#
+        );
     }
 
     #[test]
     fn test_deposit_status_serialize() {
+        let options = QueryOptions::default();
         let status = KrakenRequestBody {
             nonce: 1234567890,
-            request: Request::DepositStatus(DepositStatus { asset: Some("XBT") }),
+            otp: None,
+            request: Request::DepositStatus(DepositStatus {
+                asset: Some("XBT"),
+                options: &options,
+            }),
         };
         let json = serde_json::to_string(&status).unwrap();
         assert_eq!(json, r#"{"nonce":1234567890,"asset":"XBT"}"#);
     }
+
+    #[test]
+    fn test_deposit_status_with_options_serialize() {
+        let options = QueryOptions::new()
+            .start(1760031475)
+            .end("FTKo1pI-55ynnZ4GFwca8XsAIjxqpl")
+            .method("Bitcoin Lightning")
+            .cursor(Cursor::Start)
+            .limit(25);
+        let status = KrakenRequestBody {
+            nonce: 1234567890,
+            otp: None,
+            request: Request::DepositStatus(DepositStatus {
+                asset: Some("XBT"),
+                options: &options,
+            }),
+        };
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nonce":1234567890,"asset":"XBT","start":1760031475,"end":"FTKo1pI-55ynnZ4GFwca8XsAIjxqpl","method":"Bitcoin Lightning","cursor":true,"limit":25}"#
+        );
+    }
+
+    #[test]
+    fn test_add_order_serialize() {
+        let volume: Amount = "1.25".parse().unwrap();
+        let price: Amount = "27500".parse().unwrap();
+        let order = AddOrder::limit("XBTUSD", TrateType::Buy, volume.clone(), price)
+            .flag(OrderFlag::Post)
+            .flag(OrderFlag::Fciq)
+            .time_in_force(TimeInForce::Gtc)
+            .userref(42)
+            .validate();
+        let request = KrakenRequestBody {
+            nonce: 1234567890,
+            otp: None,
+            request: Request::AddOrder(&order),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nonce":1234567890,"ordertype":"limit","type":"buy","volume":"1.25","pair":"XBTUSD","price":"27500","oflags":"post,fciq","timeinforce":"GTC","userref":42,"validate":true}"#
+        );
+
+        let order = AddOrder::market("XBTUSD", TrateType::Sell, volume);
+        let json = serde_json::to_string(&Request::AddOrder(&order)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ordertype":"market","type":"sell","volume":"1.25","pair":"XBTUSD"}"#
+        );
+    }
+
+    #[test]
+    fn test_add_order_margin_serialize() {
+        let volume: Amount = "1.25".parse().unwrap();
+        let price: Amount = "27500".parse().unwrap();
+        let close = CloseOrder::new(OrderType::StopLossLimit, "26000".parse().unwrap())
+            .price2("25900".parse().unwrap());
+        let order = AddOrder::limit("XBTUSD", TrateType::Buy, volume.clone(), price)
+            .time_in_force(TimeInForce::Gtd)
+            .start_time(OrderTime::After(60))
+            .expire_time(OrderTime::At(1688669597))
+            .close(close)
+            .leverage(5);
+        assert!(order.check().is_ok());
+
+        let json = serde_json::to_string(&Request::AddOrder(&order)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ordertype":"limit","type":"buy","volume":"1.25","pair":"XBTUSD","price":"27500","timeinforce":"GTD","starttm":"+60","expiretm":"1688669597","close":{"ordertype":"stop-loss-limit","price":"26000","price2":"25900"},"leverage":"5"}"#
+        );
+
+        let order = AddOrder::market("XBTUSD", TrateType::Sell, volume).reduce_only();
+        let json = serde_json::to_string(&Request::AddOrder(&order)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ordertype":"market","type":"sell","volume":"1.25","pair":"XBTUSD","reduce_only":true}"#
+        );
+    }
+
+    #[test]
+    fn test_add_order_check() {
+        let order = AddOrder::limit(
+            "XBTUSD",
+            TrateType::Buy,
+            "1.25".parse().unwrap(),
+            "27500".parse().unwrap(),
+        )
+        .time_in_force(TimeInForce::Gtd);
+        assert!(matches!(order.check(), Err(Error::InvalidOrder(_))));
+
+        let order = order.expire_time(OrderTime::Now);
+        assert!(matches!(order.check(), Err(Error::InvalidOrder(_))));
+
+        let order = order.expire_time(OrderTime::After(3600));
+        assert!(order.check().is_ok());
+
+        let order = order.close(CloseOrder::new(OrderType::Other, "26000".parse().unwrap()));
+        assert!(matches!(order.check(), Err(Error::InvalidOrder(_))));
+    }
+
+    #[test]
+    fn test_deposit_addresses_serialize() {
+        let amount: Amount = "0.001".parse().unwrap();
+        let request = Request::DepositAddresses(GetDepositAddresses {
+            asset: "XBT",
+            method: "Bitcoin Lightning",
+            new: true,
+            amount: Some(&amount),
+        });
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"asset":"XBT","method":"Bitcoin Lightning","new":true,"amount":"0.001"}"#
+        );
+    }
+
+    #[test]
+    fn test_withdraw_serialize() {
+        let withdraw = Withdraw::new("XBT", "cold-storage", "0.725".parse().unwrap())
+            .max_fee("0.0001".parse().unwrap());
+        let json = serde_json::to_string(&Request::Withdraw(&withdraw)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"asset":"XBT","key":"cold-storage","amount":"0.725","max_fee":"0.0001"}"#
+        );
+    }
+
+    #[test]
+    fn test_ledgers_serialize() {
+        let options = LedgersOptions::new()
+            .assets(["XBT", "USD"])
+            .ledger_type(LedgerType::Deposit)
+            .start(1688669597);
+        let request = Request::Ledgers(GetLedgers {
+            options: &options,
+            ofs: Some(50),
+        });
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"asset":"XBT,USD","type":"deposit","start":1688669597,"ofs":50}"#
+        );
+
+        // The unknown type isn't sent as filter
+        let options = LedgersOptions::new().ledger_type(LedgerType::Other);
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(json, "{}");
+    }
+
+    #[test]
+    fn test_trades_history_serialize() {
+        let options = QueryOptions::new().start(1688669597);
+        let request = Request::TradesHistory(GetTradesHistory {
+            r#type: options.trade_type,
+            trades: true,
+            start: options.start.as_ref(),
+            end: options.end.as_ref(),
+            ofs: Some(50),
+        });
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"all","trades":true,"start":1688669597,"ofs":50}"#
+        );
+    }
+
+    #[test]
+    fn test_ohlc_serialize() {
+        let request = Request::Ohlc(GetOhlc {
+            pair: "XBTUSD",
+            interval: Some(OhlcInterval::FourHours),
+            since: None,
+        });
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"pair":"XBTUSD","interval":240}"#);
+    }
 }
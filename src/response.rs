@@ -2,10 +2,11 @@
 
 use std::collections::HashMap;
 
-use serde::{Deserialize, Deserializer, de};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
-use crate::constant::TICKERS;
-use crate::error::Error;
+use crate::amount::{Amount, deserialize_optional_amount};
+use crate::error::{Error, KrakenError, KrakenErrorSeverity};
+use crate::request::{LedgerType, LockType, OrderType};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct KrakenResult<T> {
@@ -17,60 +18,199 @@ pub(crate) struct KrakenResult<T> {
 }
 
 impl<T> KrakenResult<T> {
-    pub(crate) fn extract(self) -> Result<T, Error> {
-        if !self.error.is_empty() {
-            return Err(Error::Kraken(self.error));
+    /// Extract the result, with the warnings
+    pub(crate) fn extract(self) -> Result<(T, Vec<KrakenError>), Error> {
+        let errors: Vec<KrakenError> = self
+            .error
+            .iter()
+            .map(|e| KrakenError::from(e.as_str()))
+            .collect();
+
+        // Warnings alone don't make the request fail
+        if errors
+            .iter()
+            .any(|e| e.severity == KrakenErrorSeverity::Error)
+        {
+            return Err(Error::Kraken(errors));
         }
 
-        self.result.ok_or(Error::MissingResult)
+        let result: T = self.result.ok_or(Error::MissingResult)?;
+        Ok((result, errors))
     }
 }
 
-/// Bitcoin balances
-///
-/// This struct deserializes ONLY the bitcoin balances (see [`TICKERS`]).
-pub(crate) struct BitcoinBalances(HashMap<String, f64>);
+/// Balances of all assets
+#[derive(Deserialize)]
+pub(crate) struct Balances(pub(crate) HashMap<String, Amount>);
+
+/// Extended balance
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtendedBalance {
+    /// Total balance amount
+    pub balance: Amount,
+    /// Total held amount (open orders)
+    pub hold_trade: Amount,
+    /// Total credit amount
+    #[serde(default)]
+    pub credit: Amount,
+    /// Used credit amount
+    #[serde(default)]
+    pub credit_used: Amount,
+}
 
-impl BitcoinBalances {
-    /// Get the sum of the bitcoin balances
+impl ExtendedBalance {
+    /// Get the available balance (`balance + credit - credit_used - hold_trade`)
     #[inline]
-    pub(crate) fn sum(self) -> f64 {
-        self.0.values().sum()
+    pub fn available(&self) -> Amount {
+        &(&(&self.balance + &self.credit) - &self.credit_used) - &self.hold_trade
     }
 }
 
-impl<'de> Deserialize<'de> for BitcoinBalances {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        // Kraken returns the balances as string
-        let map: HashMap<String, String> = Deserialize::deserialize(deserializer)?;
-
-        // HashMap to store the parsed balances
-        let mut balances: HashMap<String, f64> = HashMap::new();
-
-        for (coin, amount) in map.into_iter() {
-            // If the ticker is NOT a bitcoin ticker, skip it.
-            if !TICKERS.contains(coin.as_str()) {
-                continue;
-            }
+/// Margin trade balance, valued in the base asset
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeBalance {
+    /// Equivalent balance (combined balance of all currencies)
+    #[serde(rename = "eb")]
+    pub equivalent_balance: Amount,
+    /// Trade balance (combined balance of all equity currencies)
+    #[serde(rename = "tb")]
+    pub trade_balance: Amount,
+    /// Margin amount of the open positions
+    #[serde(rename = "m")]
+    pub margin: Amount,
+    /// Unrealized net profit/loss of the open positions
+    #[serde(rename = "n")]
+    pub unrealized_pnl: Amount,
+    /// Cost basis of the open positions
+    #[serde(rename = "c")]
+    pub cost_basis: Amount,
+    /// Current floating valuation of the open positions
+    #[serde(rename = "v")]
+    pub valuation: Amount,
+    /// Equity (`trade balance + unrealized net profit/loss`)
+    #[serde(rename = "e")]
+    pub equity: Amount,
+    /// Free margin (`equity - initial margin`)
+    #[serde(rename = "mf")]
+    pub free_margin: Amount,
+    /// Margin level (`equity / initial margin * 100`), if there are open positions
+    #[serde(
+        rename = "ml",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub margin_level: Option<Amount>,
+    /// Value of the unfilled and partially filled orders
+    #[serde(
+        rename = "uv",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub unexecuted_value: Option<Amount>,
+}
 
-            // Convert to f64
-            let amount: f64 = amount.parse().map_err(de::Error::custom)?;
+/// 30-day trade volume and fee tiers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeVolume {
+    /// Volume currency
+    pub currency: String,
+    /// Current discount volume
+    pub volume: Amount,
+    /// Taker fee tiers, by asset pair
+    #[serde(default)]
+    pub fees: Option<HashMap<String, FeeTier>>,
+    /// Maker fee tiers, by asset pair (only for the pairs with a maker/taker schedule)
+    #[serde(default)]
+    pub fees_maker: Option<HashMap<String, FeeTier>>,
+}
 
-            // Insert into the balances map
-            balances.insert(coin, amount);
-        }
+/// Fee tier of an asset pair (percent)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeeTier {
+    /// Current fee
+    pub fee: Amount,
+    /// Minimum fee for the pair, if not fixed
+    #[serde(alias = "minfee")]
+    pub min_fee: Amount,
+    /// Maximum fee for the pair, if not fixed
+    #[serde(alias = "maxfee")]
+    pub max_fee: Amount,
+    /// Next tier fee, if not fixed
+    #[serde(
+        alias = "nextfee",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub next_fee: Option<Amount>,
+    /// Volume level of the current tier, if not fixed
+    #[serde(
+        alias = "tiervolume",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub tier_volume: Option<Amount>,
+    /// Volume level of the next tier, if not fixed
+    #[serde(
+        alias = "nextvolume",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub next_volume: Option<Amount>,
+}
 
-        Ok(Self(balances))
-    }
+/// Open margin position
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenPosition {
+    /// Order responsible for the position
+    #[serde(rename = "ordertxid")]
+    pub order_txid: String,
+    /// Position status (i.e., `open`)
+    #[serde(rename = "posstatus")]
+    pub status: String,
+    /// Asset pair
+    pub pair: String,
+    /// Unix timestamp of the trade
+    pub time: f64,
+    /// Direction (buy/sell) of the position
+    #[serde(rename = "type")]
+    pub r#type: TrateType,
+    /// Order type used to open the position
+    #[serde(rename = "ordertype")]
+    pub order_type: String,
+    /// Opening cost of the position (quote currency)
+    pub cost: Amount,
+    /// Opening fee of the position (quote currency)
+    pub fee: Amount,
+    /// Position volume (base currency)
+    pub vol: Amount,
+    /// Closed volume of the position (base currency)
+    pub vol_closed: Amount,
+    /// Initial margin (quote currency)
+    pub margin: Amount,
+    /// Current value of the remaining position (with `docalcs` only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub value: Option<Amount>,
+    /// Unrealized profit/loss of the remaining position (with `docalcs` only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub net: Option<Amount>,
+    /// Funding cost and term of the position
+    #[serde(default)]
+    pub terms: Option<String>,
+    /// Unix timestamp of the next margin rollover fee
+    #[serde(rename = "rollovertm", default)]
+    pub rollover_time: Option<String>,
+    /// Comma delimited list of additional info
+    #[serde(default)]
+    pub misc: String,
+    /// Comma delimited list of order flags
+    #[serde(default)]
+    pub oflags: String,
 }
 
 /// Transaction status
 ///
 /// <https://github.com/globalcitizen/ifex-protocol/blob/master/draft-ifex-00.txt#L837>
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TransactionStatus {
     /// Initial
     #[serde(alias = "initial", alias = "INITIAL")]
@@ -90,7 +230,7 @@ pub enum TransactionStatus {
 }
 
 /// Deposit transaction
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DepositTransaction {
     /// Reference ID
     #[serde(rename = "refid")]
@@ -107,11 +247,9 @@ pub struct DepositTransaction {
     /// Method transaction information
     pub info: String,
     /// Amount deposited
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub amount: f64,
+    pub amount: Amount,
     /// Fees paid
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub fee: f64,
+    pub fee: Amount,
     /// Unix timestamp when request was made
     pub time: u64,
     /// Status of deposit
@@ -119,7 +257,7 @@ pub struct DepositTransaction {
 }
 
 /// Withdraw transaction
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct WithdrawTransaction {
     /// Reference ID
     #[serde(rename = "refid")]
@@ -138,39 +276,219 @@ pub struct WithdrawTransaction {
     /// Method transaction information
     pub info: String,
     /// Amount deposited
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub amount: f64,
+    pub amount: Amount,
     /// Fees paid
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub fee: f64,
+    pub fee: Amount,
     /// Unix timestamp when request was made
     pub time: u64,
     /// Status of withdraw
     pub status: TransactionStatus,
 }
 
-/// Trades history
-#[derive(Debug, Deserialize)]
-pub(crate) struct TradesHistory {
+/// Funding (deposit/withdraw) transactions
+///
+/// The `next_cursor` is returned only if the paginated response was requested
+/// (see [`Cursor`](crate::request::Cursor)).
+#[derive(Debug)]
+pub struct FundingTransactions<T> {
+    /// Transactions
+    pub transactions: Vec<T>,
+    /// Cursor of the next page (`None` if this is the last one)
+    pub next_cursor: Option<String>,
+}
+
+impl<'de, T> Deserialize<'de> for FundingTransactions<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawFundingTransactions<T> {
+            List(Vec<T>),
+            Paginated {
+                #[serde(
+                    alias = "deposit",
+                    alias = "deposits",
+                    alias = "withdrawal",
+                    alias = "withdrawals"
+                )]
+                transactions: Vec<T>,
+                next_cursor: Option<String>,
+            },
+        }
+
+        match RawFundingTransactions::deserialize(deserializer)? {
+            RawFundingTransactions::List(transactions) => Ok(Self {
+                transactions,
+                next_cursor: None,
+            }),
+            RawFundingTransactions::Paginated {
+                transactions,
+                next_cursor,
+            } => Ok(Self {
+                transactions,
+                next_cursor,
+            }),
+        }
+    }
+}
+
+/// Page of an `ofs` paginated response
+pub(crate) trait Page {
+    /// Number of entries in this page
+    fn len(&self) -> usize;
+
+    /// Total number of entries matching the criteria
+    fn count(&self) -> u64;
+}
+
+/// Deposit method
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DepositMethod {
+    /// Name of deposit method
+    pub method: String,
+    /// Maximum net amount that can be deposited right now (`None` if there is no limit)
+    #[serde(
+        serialize_with = "serialize_limit",
+        deserialize_with = "deserialize_limit"
+    )]
+    pub limit: Option<Amount>,
+    /// Amount of fees that will be paid
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub fee: Option<Amount>,
+    /// Whether or not method has an address setup fee
+    #[serde(
+        rename = "address-setup-fee",
+        default,
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub address_setup_fee: Option<Amount>,
+    /// Whether new addresses can be generated for this method
+    #[serde(rename = "gen-address", default)]
+    pub gen_address: bool,
+    /// Minimum net amount that can be deposited right now
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub minimum: Option<Amount>,
+}
+
+/// Deposit address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DepositAddress {
+    /// Deposit address (or Lightning invoice)
+    pub address: String,
+    /// Expiration unix timestamp (`0` if the address doesn't expire)
+    #[serde(rename = "expiretm", deserialize_with = "deserialize_string_or_u64")]
+    pub expire_time: u64,
+    /// Whether or not address has ever been used
+    #[serde(default)]
+    pub new: bool,
+    /// Memo, if applicable
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Tag, if applicable
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Withdrawal method
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawMethod {
+    /// Asset
+    pub asset: String,
+    /// Name of the withdrawal method
+    pub method: String,
+    /// Network name
+    pub network: String,
+    /// Minimum net amount that can be withdrawn right now
+    pub minimum: Amount,
+}
+
+/// Withdrawal address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawAddress {
+    /// Withdrawal address
+    pub address: String,
+    /// Asset
+    pub asset: String,
+    /// Name of the withdrawal method
+    pub method: String,
+    /// Withdrawal key name, as set up on the account
+    pub key: String,
+    /// Tag, if applicable
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Memo, if applicable
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Verification status of withdrawal address
+    pub verified: bool,
+}
+
+/// Withdrawal information (fee and limit quote)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawInfo {
+    /// Name of the withdrawal method that will be used
+    pub method: String,
+    /// Maximum net amount that can be withdrawn right now
+    pub limit: Amount,
+    /// Net amount that will be sent, after fees
+    pub amount: Amount,
+    /// Amount of fees that will be paid
+    pub fee: Amount,
+}
+
+/// Withdrawal result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawResult {
+    /// Reference ID (same as [`WithdrawTransaction::id`])
+    #[serde(rename = "refid")]
+    pub id: String,
+}
+
+impl WithdrawResult {
+    /// Check if the withdraw transaction is the one created by this withdrawal
+    #[inline]
+    pub fn is_transaction(&self, tx: &WithdrawTransaction) -> bool {
+        self.id == tx.id
+    }
+}
+
+/// Trades history page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradesHistory {
     /// Trades
     pub trades: HashMap<String, Trade>,
-    // /// Count of trades
-    // pub count: usize,
+    /// Total count of trades matching the criteria
+    pub count: u64,
+}
+
+impl Page for TradesHistory {
+    fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    fn count(&self) -> u64 {
+        self.count
+    }
 }
 
 /// Trade type
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TrateType {
     /// Buy
-    #[serde(rename = "buy")]
+    #[serde(rename = "buy", alias = "b")]
     Buy,
     /// Sell
-    #[serde(rename = "sell")]
+    #[serde(rename = "sell", alias = "s")]
     Sell,
 }
 
 /// Trade history trade entry
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Trade {
     /// Unique identifier of trade executed
     #[serde(rename = "trade_id")]
@@ -184,7 +502,7 @@ pub struct Trade {
     /// Asset pair
     pub pair: String,
     /// Unix timestamp of trade
-    pub time: u64,
+    pub time: f64,
     /// Type of order (buy/sell)
     #[serde(rename = "type")]
     pub r#type: TrateType,
@@ -192,30 +510,804 @@ pub struct Trade {
     #[serde(rename = "ordertype")]
     pub order_type: String,
     /// Average price order was executed at (quote currency)
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub price: f64,
+    pub price: Amount,
     /// Total cost of order (quote currency)
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub cost: f64,
+    pub cost: Amount,
     /// Total fee (quote currency)
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub fee: f64,
+    pub fee: Amount,
     /// Volume (base currency)
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    pub vol: f64,
+    pub vol: Amount,
+}
+
+/// Order description
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderDescription {
+    /// Order description
+    pub order: String,
+    /// Conditional close order description, if applicable
+    #[serde(default)]
+    pub close: Option<String>,
+}
+
+/// Result of an order placement
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddOrderResult {
+    /// Order description
+    #[serde(rename = "descr")]
+    pub description: OrderDescription,
+    /// Transaction IDs of the placed orders (empty in validate mode)
+    #[serde(default)]
+    pub txid: Vec<String>,
+}
+
+/// Result of an order edit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditOrderResult {
+    /// Order description
+    #[serde(rename = "descr")]
+    pub description: OrderDescription,
+    /// New transaction ID (not present in validate mode)
+    #[serde(default)]
+    pub txid: Option<String>,
+    /// Original transaction ID
+    #[serde(rename = "originaltxid", default)]
+    pub original_txid: Option<String>,
+    /// Updated volume
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub volume: Option<Amount>,
+    /// Updated price
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub price: Option<Amount>,
+    /// Updated secondary price
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub price2: Option<Amount>,
+    /// Number of orders cancelled (either 0 or 1)
+    #[serde(default)]
+    pub orders_cancelled: u64,
+    /// Status of the order (`ok` or `err`)
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Error message, if the edit failed
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Result of an order cancellation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelOrderResult {
+    /// Number of orders cancelled
+    pub count: u64,
+    /// If set, order(s) is/are pending cancellation
+    #[serde(default)]
+    pub pending: bool,
+}
+
+/// WebSocket authentication token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketsToken {
+    /// Token, to be used within 15 minutes of creation
+    pub token: String,
+    /// Seconds before the token expires, if no connection is established with it
+    pub expires: u64,
+}
+
+/// Result of the dead man's switch setup
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelAllOrdersAfterResult {
+    /// Timestamp (RFC3339) reflecting when the request has been handled
+    #[serde(rename = "currentTime")]
+    pub current_time: String,
+    /// Timestamp (RFC3339) reflecting the time at which all open orders will be cancelled,
+    /// unless the timer is extended or disabled
+    #[serde(rename = "triggerTime")]
+    pub trigger_time: String,
+}
+
+/// Order status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    /// Order pending book entry
+    Pending,
+    /// Open order
+    Open,
+    /// Closed order
+    Closed,
+    /// Order canceled
+    Canceled,
+    /// Order expired
+    Expired,
+    /// Other status, not known by this library
+    #[serde(other)]
+    Other,
+}
+
+/// Order info description
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderInfoDescription {
+    /// Asset pair
+    pub pair: String,
+    /// Type of order (buy/sell)
+    #[serde(rename = "type")]
+    pub r#type: TrateType,
+    /// Order type
+    #[serde(rename = "ordertype")]
+    pub order_type: OrderType,
+    /// Primary price
+    pub price: Amount,
+    /// Secondary price
+    pub price2: Amount,
+    /// Amount of leverage
+    pub leverage: String,
+    /// Order description
+    pub order: String,
+    /// Conditional close order description, if applicable
+    pub close: String,
+}
+
+/// Order info
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderInfo {
+    /// Referral order transaction ID that created this order
+    #[serde(default)]
+    pub refid: Option<String>,
+    /// User reference ID
+    #[serde(default)]
+    pub userref: Option<i64>,
+    /// Client order ID
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+    /// Status of order
+    pub status: OrderStatus,
+    /// Unix timestamp of when order was placed
+    #[serde(rename = "opentm")]
+    pub open_time: f64,
+    /// Unix timestamp of order start time (or 0 if not set)
+    #[serde(rename = "starttm")]
+    pub start_time: f64,
+    /// Unix timestamp of order end time (or 0 if not set)
+    #[serde(rename = "expiretm")]
+    pub expire_time: f64,
+    /// Unix timestamp of when order was closed (only for closed orders)
+    #[serde(rename = "closetm", default)]
+    pub close_time: Option<f64>,
+    /// Order description
+    #[serde(rename = "descr")]
+    pub description: OrderInfoDescription,
+    /// Volume of order (base currency)
+    pub vol: Amount,
+    /// Volume executed (base currency)
+    pub vol_exec: Amount,
+    /// Total cost (quote currency)
+    pub cost: Amount,
+    /// Total fee (quote currency)
+    pub fee: Amount,
+    /// Average price (quote currency)
+    pub price: Amount,
+    /// Stop price (quote currency)
+    #[serde(rename = "stopprice")]
+    pub stop_price: Amount,
+    /// Triggered limit price (quote currency, when limit based order type triggered)
+    #[serde(rename = "limitprice")]
+    pub limit_price: Amount,
+    /// Comma delimited list of miscellaneous info
+    pub misc: String,
+    /// Comma delimited list of order flags
+    pub oflags: String,
+    /// IDs of the trades related to the order
+    #[serde(default)]
+    pub trades: Vec<String>,
+    /// Additional info on status (only for closed orders)
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Open orders
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenOrders {
+    /// Open orders by transaction ID
+    pub open: HashMap<String, OrderInfo>,
+}
+
+/// Closed orders page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClosedOrders {
+    /// Closed orders by transaction ID
+    pub closed: HashMap<String, OrderInfo>,
+    /// Total count of closed orders matching the criteria
+    pub count: u64,
+}
+
+impl Page for ClosedOrders {
+    fn len(&self) -> usize {
+        self.closed.len()
+    }
+
+    fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Ledger entry
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Reference ID
+    pub refid: String,
+    /// Unix timestamp of ledger
+    pub time: f64,
+    /// Type of ledger entry
+    #[serde(rename = "type")]
+    pub r#type: LedgerType,
+    /// Additional info relating to the ledger entry type, where applicable
+    pub subtype: String,
+    /// Asset class
+    #[serde(rename = "aclass")]
+    pub class: String,
+    /// Asset
+    pub asset: String,
+    /// Transaction amount
+    pub amount: Amount,
+    /// Transaction fee
+    pub fee: Amount,
+    /// Resulting balance
+    pub balance: Amount,
+}
+
+/// Ledgers page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ledgers {
+    /// Ledger entries by ledger ID
+    pub ledger: HashMap<String, LedgerEntry>,
+    /// Total count of ledger entries matching the criteria
+    pub count: u64,
+}
+
+impl Page for Ledgers {
+    fn len(&self) -> usize {
+        self.ledger.len()
+    }
+
+    fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Ask/bid price level
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickerLevel {
+    /// Price
+    pub price: Amount,
+    /// Whole lot volume
+    pub whole_lot_volume: Amount,
+    /// Lot volume
+    pub lot_volume: Amount,
+}
+
+/// Last trade closed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickerLastTrade {
+    /// Price
+    pub price: Amount,
+    /// Lot volume
+    pub lot_volume: Amount,
+}
+
+/// Value for today and the last 24 hours
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickerDailyValue {
+    /// Today
+    pub today: Amount,
+    /// Last 24 hours
+    pub last_24h: Amount,
+}
+
+/// Number of trades for today and the last 24 hours
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickerDailyCount {
+    /// Today
+    pub today: u64,
+    /// Last 24 hours
+    pub last_24h: u64,
+}
+
+/// Asset information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetInfo {
+    /// Asset class
+    #[serde(rename = "aclass")]
+    pub asset_class: String,
+    /// Alternate name (i.e., `XBT` for `XXBT`)
+    pub altname: String,
+    /// Scaling decimal places for record keeping
+    pub decimals: u32,
+    /// Scaling decimal places for output display
+    pub display_decimals: u32,
+    /// Status (i.e., `enabled`, `deposit_only`)
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Asset pair information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetPairInfo {
+    /// Alternate name (i.e., `XBTUSD` for `XXBTZUSD`)
+    pub altname: String,
+    /// WebSocket name (i.e., `XBT/USD`)
+    #[serde(default)]
+    pub wsname: Option<String>,
+    /// Base asset (i.e., `XXBT`)
+    pub base: String,
+    /// Quote asset (i.e., `ZUSD`)
+    pub quote: String,
+    /// Scaling decimal places for the price
+    pub pair_decimals: u32,
+    /// Scaling decimal places for the cost
+    pub cost_decimals: u32,
+    /// Scaling decimal places for the volume
+    pub lot_decimals: u32,
+    /// Amount to multiply the lot volume by, to get the currency volume
+    pub lot_multiplier: u32,
+    /// Minimum order size (in base currency)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub ordermin: Option<Amount>,
+    /// Minimum order cost (in quote currency)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub costmin: Option<Amount>,
+    /// Minimum price increment (in quote currency)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub tick_size: Option<Amount>,
+    /// Status (i.e., `online`, `cancel_only`)
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Ticker information
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickerInfo {
+    /// Ask
+    #[serde(rename = "a")]
+    pub ask: TickerLevel,
+    /// Bid
+    #[serde(rename = "b")]
+    pub bid: TickerLevel,
+    /// Last trade closed
+    #[serde(rename = "c")]
+    pub last_trade: TickerLastTrade,
+    /// Volume
+    #[serde(rename = "v")]
+    pub volume: TickerDailyValue,
+    /// Volume weighted average price
+    #[serde(rename = "p")]
+    pub vwap: TickerDailyValue,
+    /// Number of trades
+    #[serde(rename = "t")]
+    pub trades: TickerDailyCount,
+    /// Low
+    #[serde(rename = "l")]
+    pub low: TickerDailyValue,
+    /// High
+    #[serde(rename = "h")]
+    pub high: TickerDailyValue,
+    /// Today's opening price
+    #[serde(rename = "o")]
+    pub open: Amount,
+}
+
+/// OHLC candle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Candle {
+    /// Unix timestamp of the candle
+    pub time: u64,
+    /// Open
+    pub open: Amount,
+    /// High
+    pub high: Amount,
+    /// Low
+    pub low: Amount,
+    /// Close
+    pub close: Amount,
+    /// Volume weighted average price
+    pub vwap: Amount,
+    /// Volume
+    pub volume: Amount,
+    /// Number of trades
+    pub count: u64,
+}
+
+/// OHLC data
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ohlc {
+    /// ID to be used as `since` when polling for new, committed OHLC data
+    pub last: u64,
+    /// Candles by asset pair
+    #[serde(flatten)]
+    pub pairs: HashMap<String, Vec<Candle>>,
+}
+
+/// Order book entry
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderBookEntry {
+    /// Price
+    pub price: Amount,
+    /// Volume
+    pub volume: Amount,
+    /// Unix timestamp
+    pub timestamp: u64,
+}
+
+/// Order book
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderBook {
+    /// Ask side
+    pub asks: Vec<OrderBookEntry>,
+    /// Bid side
+    pub bids: Vec<OrderBookEntry>,
+}
+
+/// Order type of a public trade
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PublicTradeOrderType {
+    /// Market
+    #[serde(rename = "m")]
+    Market,
+    /// Limit
+    #[serde(rename = "l")]
+    Limit,
+}
+
+/// Public trade
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublicTrade {
+    /// Price
+    pub price: Amount,
+    /// Volume
+    pub volume: Amount,
+    /// Unix timestamp
+    pub time: f64,
+    /// Buy/sell
+    pub side: TrateType,
+    /// Market/limit
+    pub order_type: PublicTradeOrderType,
+    /// Miscellaneous
+    pub misc: String,
+    /// Trade ID
+    pub trade_id: u64,
+}
+
+/// Recent trades
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentTrades {
+    /// ID to be used as `since` when polling for new trade data
+    #[serde(deserialize_with = "deserialize_string_or_u64")]
+    pub last: u64,
+    /// Trades by asset pair
+    #[serde(flatten)]
+    pub pairs: HashMap<String, Vec<PublicTrade>>,
+}
+
+/// Spread
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Spread {
+    /// Unix timestamp
+    pub time: u64,
+    /// Bid
+    pub bid: Amount,
+    /// Ask
+    pub ask: Amount,
+}
+
+/// Recent spreads
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentSpreads {
+    /// ID to be used as `since` when polling for new spread data
+    pub last: u64,
+    /// Spreads by asset pair
+    #[serde(flatten)]
+    pub pairs: HashMap<String, Vec<Spread>>,
 }
 
-fn deserialize_string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+/// Deserialize a limit, which is either `false` (no limit) or an amount
+fn deserialize_limit<'de, D>(deserializer: D) -> Result<Option<Amount>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = String::deserialize(deserializer)?;
-    s.parse().map_err(de::Error::custom)
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Limit {
+        Bool(bool),
+        String(String),
+    }
+
+    match Limit::deserialize(deserializer)? {
+        Limit::Bool(false) => Ok(None),
+        Limit::Bool(true) => Err(de::Error::custom("invalid limit: true")),
+        Limit::String(s) => s.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// Serialize a limit back as `false` if there is no limit
+fn serialize_limit<S>(limit: &Option<Amount>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match limit {
+        Some(limit) => limit.serialize(serializer),
+        None => serializer.serialize_bool(false),
+    }
+}
+
+fn deserialize_string_or_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrU64 {
+        String(String),
+        U64(u64),
+    }
+
+    match StringOrU64::deserialize(deserializer)? {
+        StringOrU64::String(s) => s.parse().map_err(de::Error::custom),
+        StringOrU64::U64(n) => Ok(n),
+    }
+}
+
+/// Earn strategies page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnStrategies {
+    /// Strategies
+    pub items: Vec<EarnStrategy>,
+    /// Cursor of the next page, if any
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// Earn strategy
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnStrategy {
+    /// Strategy ID
+    pub id: String,
+    /// Asset to allocate (i.e., `DOT`)
+    pub asset: String,
+    /// Lock type and periods
+    pub lock_type: StrategyLockType,
+    /// Estimated APR range
+    #[serde(default)]
+    pub apr_estimate: Option<AprEstimate>,
+    /// Minimum allocation amount
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub user_min_allocation: Option<Amount>,
+    /// Maximum allocation amount
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub user_cap: Option<Amount>,
+    /// Fee applied when allocating
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub allocation_fee: Option<Amount>,
+    /// Fee applied when deallocating
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub deallocation_fee: Option<Amount>,
+    /// Auto compounding of the rewards
+    pub auto_compound: AutoCompound,
+    /// Source of the rewards
+    pub yield_source: YieldSource,
+    /// Whether new allocations are accepted
+    pub can_allocate: bool,
+    /// Whether the allocations can be deallocated
+    pub can_deallocate: bool,
+    /// Reasons preventing the allocation, if any
+    #[serde(default)]
+    pub allocation_restriction_info: Vec<String>,
+}
+
+/// Earn strategy lock type and periods (in seconds)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyLockType {
+    /// Lock type
+    #[serde(rename = "type")]
+    pub r#type: LockType,
+    /// Rewards payout frequency
+    #[serde(default)]
+    pub payout_frequency: Option<u64>,
+    /// Bonding period, before earning rewards
+    #[serde(default)]
+    pub bonding_period: Option<u64>,
+    /// Whether the bonding period is variable
+    #[serde(default)]
+    pub bonding_period_variable: Option<bool>,
+    /// Whether rewards are earned during the bonding period
+    #[serde(default)]
+    pub bonding_rewards: Option<bool>,
+    /// Unbonding period, before the funds are available
+    #[serde(default)]
+    pub unbonding_period: Option<u64>,
+    /// Whether the unbonding period is variable
+    #[serde(default)]
+    pub unbonding_period_variable: Option<bool>,
+    /// Whether rewards are earned during the unbonding period
+    #[serde(default)]
+    pub unbonding_rewards: Option<bool>,
+    /// Exit queue period, before the unbonding period
+    #[serde(default)]
+    pub exit_queue_period: Option<u64>,
+}
+
+/// Estimated APR range (percent)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AprEstimate {
+    /// Minimum estimated APR
+    pub low: Amount,
+    /// Maximum estimated APR
+    pub high: Amount,
+}
+
+/// Auto compounding of the earn rewards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutoCompound {
+    /// Always compounded
+    Enabled,
+    /// Never compounded
+    Disabled,
+    /// Chosen by the user
+    Optional {
+        /// Compounded by default
+        #[serde(default)]
+        default: bool,
+    },
+}
+
+/// Source of the earn rewards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum YieldSource {
+    /// On-chain staking
+    Staking,
+    /// Opt-in rewards
+    OptInRewards,
+    /// Other
+    #[serde(other)]
+    Other,
+}
+
+/// Status of the last allocation or deallocation request of a strategy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EarnOperationStatus {
+    /// Whether the request is still being processed
+    pub pending: bool,
+}
+
+/// Earn allocations, with the rewards
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnAllocations {
+    /// Asset of the converted amounts (i.e., `USD`)
+    pub converted_asset: String,
+    /// Total allocated amount, in the converted asset
+    pub total_allocated: Amount,
+    /// Total rewarded amount, in the converted asset
+    pub total_rewarded: Amount,
+    /// Cursor of the next page, if any
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    /// Allocations, by strategy
+    pub items: Vec<EarnAllocation>,
+}
+
+/// Earn allocation of a strategy
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnAllocation {
+    /// Strategy ID
+    pub strategy_id: String,
+    /// Allocated asset
+    pub native_asset: String,
+    /// Allocated amounts, by state
+    pub amount_allocated: AllocatedAmounts,
+    /// Total rewarded amount
+    pub total_rewarded: EarnAmount,
+    /// Current rewards payout period, if any
+    #[serde(default)]
+    pub payout: Option<EarnPayout>,
+}
+
+/// Amount in the native and in the converted asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnAmount {
+    /// Amount in the native asset
+    pub native: Amount,
+    /// Amount in the converted asset
+    pub converted: Amount,
+}
+
+/// Allocated amounts, by state
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AllocatedAmounts {
+    /// Total allocated amount
+    pub total: EarnAmount,
+    /// Amount being bonded
+    #[serde(default)]
+    pub bonding: Option<AllocationState>,
+    /// Amount in the exit queue
+    #[serde(default)]
+    pub exit_queue: Option<AllocationState>,
+    /// Amount being allocated
+    #[serde(default)]
+    pub pending: Option<EarnAmount>,
+    /// Amount being unbonded
+    #[serde(default)]
+    pub unbonding: Option<AllocationState>,
+}
+
+/// Allocated amount in a bonding/unbonding state
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AllocationState {
+    /// Amount in the native asset
+    pub native: Amount,
+    /// Amount in the converted asset
+    pub converted: Amount,
+    /// Number of allocations in this state
+    pub allocation_count: u64,
+    /// Allocations in this state
+    #[serde(default)]
+    pub allocations: Vec<TimedAllocation>,
+}
+
+/// Allocation in a bonding/unbonding state
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimedAllocation {
+    /// Timestamp (RFC3339) of the allocation
+    pub created_at: String,
+    /// Timestamp (RFC3339) of the end of the state
+    pub expires: String,
+    /// Amount in the native asset
+    pub native: Amount,
+    /// Amount in the converted asset
+    pub converted: Amount,
+}
+
+/// Rewards payout period
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnPayout {
+    /// Rewards accumulated in the period
+    pub accumulated_reward: EarnAmount,
+    /// Estimated rewards of the period
+    pub estimated_reward: EarnAmount,
+    /// Timestamp (RFC3339) of the period start
+    pub period_start: String,
+    /// Timestamp (RFC3339) of the period end
+    pub period_end: String,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::amount::tests::assert_amount;
+    use crate::asset::AssetFamily;
+    use crate::error::KrakenErrorMessage;
+
+    #[test]
+    fn test_result_extract_errors() {
+        let json = r#"{"error": ["EAPI:Invalid nonce"]}"#;
+        let result: KrakenResult<u64> = serde_json::from_str(json).unwrap();
+        match result.extract() {
+            Err(Error::Kraken(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].message, KrakenErrorMessage::InvalidNonce);
+            }
+            _ => panic!("Expected kraken error"),
+        }
+
+        let json = r#"{"error": ["WGeneral:Something"], "result": 1}"#;
+        let result: KrakenResult<u64> = serde_json::from_str(json).unwrap();
+        let (result, warnings) = result.extract().unwrap();
+        assert_eq!(result, 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, KrakenErrorSeverity::Warning);
+        assert_eq!(warnings[0].raw, "WGeneral:Something");
+
+        let json = r#"{"error": [], "result": 1}"#;
+        let result: KrakenResult<u64> = serde_json::from_str(json).unwrap();
+        assert!(result.extract().unwrap().1.is_empty());
+    }
 
     #[test]
     fn test_balances_deserialize_and_sum() {
@@ -226,17 +1318,14 @@ mod tests {
             "USD": "1000.50"
         }"#;
 
-        let balances: BitcoinBalances = serde_json::from_str(json).expect("Failed to deserialize");
+        let balances: Balances = serde_json::from_str(json).expect("Failed to deserialize");
 
-        assert_eq!(balances.0.len(), 2);
-        assert!(balances.0.contains_key("XBT"));
-        assert!(balances.0.contains_key("XBT.F"));
-        assert!(!balances.0.contains_key("ETH"));
-        assert!(!balances.0.contains_key("USD"));
+        assert_eq!(balances.0.len(), 4);
 
         // Check sum
-        let sum = balances.sum();
-        assert!((sum - 3.8).abs() < 0.0001);
+        let sum = AssetFamily::bitcoin().sum(&balances.0);
+        assert_amount(&sum, "3.8");
+        assert_amount(&AssetFamily::new("ETH").sum(&balances.0), "10.0");
     }
 
     #[test]
@@ -245,7 +1334,7 @@ mod tests {
             "XBT": "invalid_number"
         }"#;
 
-        let result: Result<BitcoinBalances, _> = serde_json::from_str(json);
+        let result: Result<Balances, _> = serde_json::from_str(json);
         assert!(result.is_err());
     }
 
@@ -256,9 +1345,36 @@ mod tests {
             "USD": "1000.50"
         }"#;
 
-        let balances: BitcoinBalances = serde_json::from_str(json).expect("Failed to deserialize");
-        assert_eq!(balances.0.len(), 0);
-        assert_eq!(balances.sum(), 0.0);
+        let balances: Balances = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_amount(&AssetFamily::bitcoin().sum(&balances.0), "0");
+    }
+
+    #[test]
+    fn test_all_balances_deserialize() {
+        let json = r#"{
+            "XXBT": "1.5",
+            "ZUSD": "1000.50"
+        }"#;
+
+        let balances: Balances = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(balances.0.len(), 2);
+        assert_amount(balances.0.get("ZUSD").unwrap(), "1000.5");
+    }
+
+    #[test]
+    fn test_extended_balances_deserialize() {
+        let json = r#"{
+            "ZUSD": {"balance": "25435.21", "hold_trade": "8249.76"},
+            "XXBT": {"balance": "1.2435", "hold_trade": "0.8423", "credit": "0.5", "credit_used": "0.1"}
+        }"#;
+
+        let balances: HashMap<String, ExtendedBalance> =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        let usd = balances.get("ZUSD").unwrap();
+        assert_amount(&usd.credit, "0");
+        assert_amount(&usd.available(), "17185.45");
+        let btc = balances.get("XXBT").unwrap();
+        assert_amount(&btc.available(), "0.8012");
     }
 
     #[test]
@@ -267,10 +1383,401 @@ mod tests {
 
         let tx: DepositTransaction = serde_json::from_str(json).expect("Failed to deserialize");
         assert_eq!(tx.class, "currency");
-        assert_eq!(tx.amount, 0.00005);
-        assert_eq!(tx.fee, 0.0);
+        assert_amount(&tx.amount, "0.00005");
+        assert_amount(&tx.fee, "0");
         assert_eq!(tx.method, "Bitcoin Lightning");
         assert_eq!(tx.status, TransactionStatus::Success);
         assert_eq!(tx.time, 1760031475);
     }
+
+    #[test]
+    fn test_funding_transactions_deserialization() {
+        let tx = r#"{"aclass": "currency", "amount": "0.0000500000", "asset": "XXBT", "fee": "0.0000000000", "info": "lnbc", "method": "Bitcoin Lightning", "refid": "FTKo1pI-55ynnZ4GFwca8XsAIjxqpl", "status": "Success", "time": 1760031475, "txid": "16e97a57"}"#;
+
+        let json = format!("[{tx}]");
+        let deposits: FundingTransactions<DepositTransaction> =
+            serde_json::from_str(&json).expect("Failed to deserialize");
+        assert_eq!(deposits.transactions.len(), 1);
+        assert_eq!(deposits.next_cursor, None);
+
+        let json = format!(r#"{{"deposit": [{tx}], "next_cursor": "abc"}}"#);
+        let deposits: FundingTransactions<DepositTransaction> =
+            serde_json::from_str(&json).expect("Failed to deserialize");
+        assert_eq!(deposits.transactions.len(), 1);
+        assert_eq!(deposits.next_cursor.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_deposit_methods_deserialization() {
+        let json = r#"[{"method": "Bitcoin", "limit": false, "fee": "0.0000000000", "gen-address": true, "minimum": "0.00010000"}, {"method": "Bitcoin Lightning", "limit": "5.0000000000", "fee": "0.00000000", "minimum": "0.00001000"}]"#;
+
+        let methods: Vec<DepositMethod> =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0].limit, None);
+        assert!(methods[0].gen_address);
+        assert_amount(methods[0].minimum.as_ref().unwrap(), "0.0001");
+        assert_amount(methods[1].limit.as_ref().unwrap(), "5.0000000000");
+        assert!(!methods[1].gen_address);
+    }
+
+    #[test]
+    fn test_amount_round_trip() {
+        let json = r#"{"method":"Bitcoin","limit":false,"fee":"0.0000500000","address-setup-fee":null,"gen-address":true,"minimum":"0.00010000"}"#;
+
+        let method: DepositMethod = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(serde_json::to_string(&method).unwrap(), json);
+
+        let json = r#"{"fee":"0.1000","min_fee":"0.1000","max_fee":"0.2600","next_fee":"0.0900","tier_volume":"10000000.0000","next_volume":"25000000.0000"}"#;
+
+        let tier: FeeTier = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(tier.fee.as_str(), "0.1000");
+        assert_eq!(serde_json::to_string(&tier).unwrap(), json);
+    }
+
+    #[test]
+    fn test_deposit_addresses_deserialization() {
+        let json = r#"[{"address": "2N9fRkx5JTWXWHmXzZtvhQsufvoYRMq9ExV", "expiretm": "0", "new": true}, {"address": "lnbc10u1p5", "expiretm": 1688669597}]"#;
+
+        let addresses: Vec<DepositAddress> =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(addresses[0].expire_time, 0);
+        assert!(addresses[0].new);
+        assert_eq!(addresses[1].expire_time, 1688669597);
+        assert!(!addresses[1].new);
+    }
+
+    #[test]
+    fn test_withdraw_info_deserialization() {
+        let json = r#"{"method": "Bitcoin", "limit": "332.00956139", "fee": "0.0000000000", "amount": "0.7250000000"}"#;
+
+        let info: WithdrawInfo = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(info.method, "Bitcoin");
+        assert_amount(&info.limit, "332.00956139");
+        assert_amount(&info.amount, "0.725");
+        assert_amount(&info.fee, "0");
+    }
+
+    #[test]
+    fn test_trades_history_deserialization() {
+        let json = r#"{"trades": {"THVRQM-33VKH-UCI7BS": {"ordertxid": "OQCLML-BW3P3-BUCMWZ", "postxid": "TKH2SE-M7IF5-CFI7LT", "pair": "XXBTZUSD", "time": 1688667796.8802, "type": "buy", "ordertype": "limit", "price": "30010.00000", "cost": "600.20000", "fee": "0.00000", "vol": "0.02000000", "margin": "0.00000", "misc": "", "trade_id": "40274859", "maker": true}}, "count": 2346}"#;
+
+        let history: TradesHistory = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.count(), 2346);
+        let trade = history.trades.get("THVRQM-33VKH-UCI7BS").unwrap();
+        assert_eq!(trade.id, "40274859");
+        assert_amount(&trade.vol, "0.02");
+        assert_eq!(trade.time, 1688667796.8802);
+    }
+
+    #[test]
+    fn test_add_order_result_deserialization() {
+        let json = r#"{"descr": {"order": "buy 1.25000000 XBTUSD @ limit 27500.0"}, "txid": ["OU22CG-KLAF2-FWUDD7"]}"#;
+
+        let result: AddOrderResult = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(
+            result.description.order,
+            "buy 1.25000000 XBTUSD @ limit 27500.0"
+        );
+        assert_eq!(result.description.close, None);
+        assert_eq!(result.txid, vec![String::from("OU22CG-KLAF2-FWUDD7")]);
+
+        // Validate mode
+        let json = r#"{"descr": {"order": "buy 1.25000000 XBTUSD @ market"}}"#;
+        let result: AddOrderResult = serde_json::from_str(json).expect("Failed to deserialize");
+        assert!(result.txid.is_empty());
+    }
+
+    #[test]
+    fn test_closed_orders_deserialization() {
+        let json = r#"{"closed": {"O37652-RJWRT-IMO74O": {"refid": "None", "userref": 1, "status": "canceled", "reason": "User requested", "opentm": 1688148493.7708, "closetm": 1688148610.0482, "starttm": 0, "expiretm": 0, "descr": {"pair": "XBTGBP", "type": "buy", "ordertype": "stop-loss-limit", "price": "23667.0", "price2": "0", "leverage": "none", "order": "buy 0.00100000 XBTGBP @ limit 23667.0", "close": ""}, "vol": "0.00100000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "stopprice": "0.00000", "limitprice": "0.00000", "misc": "", "oflags": "fciq", "trades": ["TZX2WP-XSEOP-FP7WYR"]}}, "count": 1}"#;
+
+        let orders: ClosedOrders = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders.count(), 1);
+        let order = orders.closed.get("O37652-RJWRT-IMO74O").unwrap();
+        assert_eq!(order.status, OrderStatus::Canceled);
+        assert_eq!(order.userref, Some(1));
+        assert_eq!(order.close_time, Some(1688148610.0482));
+        assert_eq!(order.description.order_type, OrderType::StopLossLimit);
+        assert_amount(&order.description.price, "23667");
+        assert_amount(&order.vol, "0.001");
+        assert_eq!(order.trades, vec![String::from("TZX2WP-XSEOP-FP7WYR")]);
+    }
+
+    #[test]
+    fn test_unknown_order_deserialization() {
+        let json = r#"{"open": {"OB5VMB-B4U2U-DK2WRW": {"refid": null, "userref": 0, "status": "suspended", "opentm": 1688666559.8974, "starttm": 0, "expiretm": 0, "descr": {"pair": "XBTUSD", "type": "sell", "ordertype": "one-cancels-other", "price": "30010.0", "price2": "0", "leverage": "none", "order": "sell 0.01000000 XBTUSD @ oco 30010.0", "close": ""}, "vol": "0.01000000", "vol_exec": "0.00000000", "cost": "0.00000", "fee": "0.00000", "price": "0.00000", "stopprice": "0.00000", "limitprice": "0.00000", "misc": "", "oflags": "fciq"}}}"#;
+
+        let orders: OpenOrders = serde_json::from_str(json).expect("Failed to deserialize");
+        let order = orders.open.get("OB5VMB-B4U2U-DK2WRW").unwrap();
+        assert_eq!(order.status, OrderStatus::Other);
+        assert_eq!(order.description.order_type, OrderType::Other);
+    }
+
+    #[test]
+    fn test_ledgers_deserialization() {
+        let json = r#"{"ledger": {"L4UESK-KG3EQ-UFO4T5": {"refid": "TJKLXX-PGMUI-4NTLXU", "time": 1688464484.1787, "type": "trade", "subtype": "", "aclass": "currency", "asset": "ZGBP", "amount": "-24.5000", "fee": "0.0490", "balance": "459567.9171"}, "L7QA3H-NXTGD-ZQ3K6X": {"refid": "FTdmYzR-ALOCW-2FYD1H", "time": 1688464484.1787, "type": "staking", "subtype": "", "aclass": "currency", "asset": "DOT.S", "amount": "0.0100000000", "fee": "0.0000000000", "balance": "1.0100000000"}}, "count": 2}"#;
+
+        let ledgers: Ledgers = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(ledgers.len(), 2);
+        assert_eq!(ledgers.count(), 2);
+        let entry = ledgers.ledger.get("L4UESK-KG3EQ-UFO4T5").unwrap();
+        assert_eq!(entry.r#type, LedgerType::Trade);
+        assert_amount(&entry.amount, "-24.5");
+        let entry = ledgers.ledger.get("L7QA3H-NXTGD-ZQ3K6X").unwrap();
+        assert_eq!(entry.r#type, LedgerType::Staking);
+        assert_amount(&entry.balance, "1.01");
+    }
+
+    #[test]
+    fn test_ticker_deserialization() {
+        let json = r#"{"XXBTZUSD": {"a": ["30300.10000", "1", "1.000"], "b": ["30300.00000", "1", "1.000"], "c": ["30303.20000", "0.00067643"], "v": ["4083.67001100", "4412.73601799"], "p": ["30706.77771", "30689.13205"], "t": [34619, 38907], "l": ["29868.30000", "29868.30000"], "h": ["31631.00000", "31631.00000"], "o": "30502.80000"}}"#;
+
+        let ticker: HashMap<String, TickerInfo> =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        let info = ticker.get("XXBTZUSD").unwrap();
+        assert_amount(&info.ask.price, "30300.1");
+        assert_amount(&info.bid.lot_volume, "1");
+        assert_amount(&info.last_trade.lot_volume, "0.00067643");
+        assert_eq!(info.trades.last_24h, 38907);
+        assert_amount(&info.open, "30502.8");
+    }
+
+    #[test]
+    fn test_ohlc_deserialization() {
+        let json = r#"{"XXBTZUSD": [[1688671200, "30306.1", "30306.2", "30305.7", "30305.7", "30306.1", "3.39243896", 23], [1688671260, "30304.5", "30304.5", "30300.0", "30300.0", "30300.2", "4.42996871", 18]], "last": 1688672160}"#;
+
+        let ohlc: Ohlc = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(ohlc.last, 1688672160);
+        let candles = ohlc.pairs.get("XXBTZUSD").unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].time, 1688671200);
+        assert_amount(&candles[1].close, "30300");
+        assert_eq!(candles[1].count, 18);
+    }
+
+    #[test]
+    fn test_recent_trades_deserialization() {
+        let json = r#"{"XXBTZUSD": [["30243.40000", "0.34507674", 1688669597.8277369, "b", "m", "", 61044952], ["30243.30000", "0.00376960", 1688669598.2804112, "s", "l", "", 61044953]], "last": "1688671969993150842"}"#;
+
+        let trades: RecentTrades = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(trades.last, 1688671969993150842);
+        let trades = trades.pairs.get("XXBTZUSD").unwrap();
+        assert!(matches!(trades[0].side, TrateType::Buy));
+        assert!(matches!(trades[1].order_type, PublicTradeOrderType::Limit));
+        assert_eq!(trades[1].trade_id, 61044953);
+    }
+
+    #[test]
+    fn test_earn_strategies_deserialize() {
+        let json = r#"{
+            "items": [{
+                "id": "ESRFUO3-Q62XD-WIOIL7",
+                "asset": "DOT",
+                "lock_type": {
+                    "type": "bonded",
+                    "payout_frequency": 604800,
+                    "bonding_period": 0,
+                    "bonding_period_variable": false,
+                    "bonding_rewards": false,
+                    "unbonding_period": 2419200,
+                    "unbonding_period_variable": false,
+                    "unbonding_rewards": false,
+                    "exit_queue_period": 0
+                },
+                "apr_estimate": {"low": "8.0000", "high": "12.0000"},
+                "user_min_allocation": "0.01",
+                "allocation_fee": "0.0000",
+                "deallocation_fee": 0,
+                "auto_compound": {"type": "enabled"},
+                "yield_source": {"type": "staking"},
+                "can_allocate": true,
+                "can_deallocate": true,
+                "allocation_restriction_info": []
+            }, {
+                "id": "ESMWVX6-JAPVY-23L3CV",
+                "asset": "USDC",
+                "lock_type": {"type": "flex"},
+                "auto_compound": {"type": "optional", "default": true},
+                "yield_source": {"type": "opt_in_rewards"},
+                "can_allocate": false,
+                "can_deallocate": true,
+                "allocation_restriction_info": ["tier"]
+            }],
+            "next_cursor": "2"
+        }"#;
+
+        let strategies: EarnStrategies = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(strategies.next_cursor.as_deref(), Some("2"));
+
+        let bonded = &strategies.items[0];
+        assert_eq!(bonded.lock_type.r#type, LockType::Bonded);
+        assert_eq!(bonded.lock_type.unbonding_period, Some(2419200));
+        assert_amount(&bonded.apr_estimate.as_ref().unwrap().high, "12");
+        assert_amount(bonded.deallocation_fee.as_ref().unwrap(), "0");
+        assert_eq!(bonded.auto_compound, AutoCompound::Enabled);
+        assert_eq!(bonded.yield_source, YieldSource::Staking);
+
+        let flex = &strategies.items[1];
+        assert_eq!(flex.lock_type.r#type, LockType::Flex);
+        assert!(flex.apr_estimate.is_none());
+        assert_eq!(flex.auto_compound, AutoCompound::Optional { default: true });
+        assert_eq!(flex.yield_source, YieldSource::OptInRewards);
+        assert!(!flex.can_allocate);
+    }
+
+    #[test]
+    fn test_earn_allocations_deserialize() {
+        let json = r#"{
+            "converted_asset": "USD",
+            "total_allocated": "49.2398",
+            "total_rewarded": "0.0675",
+            "next_cursor": null,
+            "items": [{
+                "strategy_id": "ESDQCOL-WTZEU-NU55QF",
+                "native_asset": "ETH",
+                "amount_allocated": {
+                    "bonding": {
+                        "native": "0.0210000000",
+                        "converted": "39.0645",
+                        "allocation_count": 1,
+                        "allocations": [{
+                            "created_at": "2023-07-06T10:52:05Z",
+                            "expires": "2023-08-19T02:34:05.807Z",
+                            "native": "0.0210000000",
+                            "converted": "39.0645"
+                        }]
+                    },
+                    "total": {"native": "0.0210000000", "converted": "39.0645"}
+                },
+                "total_rewarded": {"native": "0", "converted": "0.0000"},
+                "payout": {
+                    "period_start": "2023-07-06T00:00:00Z",
+                    "period_end": "2023-07-13T00:00:00Z",
+                    "accumulated_reward": {"native": "0.0001", "converted": "0.19"},
+                    "estimated_reward": {"native": "0.0002", "converted": "0.38"}
+                }
+            }]
+        }"#;
+
+        let allocations: EarnAllocations =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(allocations.converted_asset, "USD");
+        assert_amount(&allocations.total_allocated, "49.2398");
+        assert!(allocations.next_cursor.is_none());
+
+        let allocation = &allocations.items[0];
+        assert_eq!(allocation.native_asset, "ETH");
+        assert_amount(&allocation.amount_allocated.total.native, "0.021");
+
+        let bonding = allocation.amount_allocated.bonding.as_ref().unwrap();
+        assert_eq!(bonding.allocation_count, 1);
+        assert_eq!(bonding.allocations[0].created_at, "2023-07-06T10:52:05Z");
+        assert!(allocation.amount_allocated.unbonding.is_none());
+
+        let payout = allocation.payout.as_ref().unwrap();
+        assert_amount(&payout.estimated_reward.converted, "0.38");
+    }
+
+    #[test]
+    fn test_trade_balance_deserialize() {
+        let json = r#"{
+            "eb": "1101.3425",
+            "tb": "392.2264",
+            "m": "7.0354",
+            "n": "-10.0232",
+            "c": "21.1063",
+            "v": "31.1297",
+            "e": "382.2032",
+            "mf": "375.1678",
+            "ml": "5432.57"
+        }"#;
+
+        let balance: TradeBalance = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_amount(&balance.equity, "382.2032");
+        assert_amount(&balance.free_margin, "375.1678");
+        assert_amount(&balance.unrealized_pnl, "-10.0232");
+        assert_amount(&balance.margin_level.unwrap(), "5432.57");
+        assert!(balance.unexecuted_value.is_none());
+    }
+
+    #[test]
+    fn test_trade_volume_deserialize() {
+        let json = r#"{
+            "currency": "ZUSD",
+            "volume": "200709587.4223",
+            "fees": {
+                "XXBTZUSD": {
+                    "fee": "0.1000",
+                    "minfee": "0.1000",
+                    "maxfee": "0.2600",
+                    "nextfee": null,
+                    "nextvolume": null,
+                    "tiervolume": "10000000.0000"
+                }
+            },
+            "fees_maker": {
+                "XXBTZUSD": {
+                    "fee": "0.0000",
+                    "min_fee": "0.0000",
+                    "max_fee": "0.1600",
+                    "next_fee": null,
+                    "next_volume": null,
+                    "tier_volume": "10000000.0000"
+                }
+            }
+        }"#;
+
+        let volume: TradeVolume = serde_json::from_str(json).expect("Failed to deserialize");
+        assert_eq!(volume.currency, "ZUSD");
+
+        let taker = &volume.fees.as_ref().unwrap()["XXBTZUSD"];
+        assert_amount(&taker.fee, "0.1");
+        assert_amount(&taker.max_fee, "0.26");
+        assert!(taker.next_fee.is_none());
+
+        let maker = &volume.fees_maker.as_ref().unwrap()["XXBTZUSD"];
+        assert_amount(&maker.max_fee, "0.16");
+        assert_amount(maker.tier_volume.as_ref().unwrap(), "10000000");
+
+        let json = r#"{"currency": "ZUSD", "volume": "0.0000"}"#;
+        let volume: TradeVolume = serde_json::from_str(json).expect("Failed to deserialize");
+        assert!(volume.fees.is_none());
+    }
+
+    #[test]
+    fn test_open_positions_deserialize() {
+        let json = r#"{
+            "TF5GVO-T7ZZ2-6NBKBI": {
+                "ordertxid": "OLWNFG-LLH4R-D6SFFP",
+                "posstatus": "open",
+                "pair": "XXBTZUSD",
+                "time": 1605280097.8294,
+                "type": "buy",
+                "ordertype": "limit",
+                "cost": "104610.52842",
+                "fee": "289.06565",
+                "vol": "8.82412861",
+                "vol_closed": "0.20200000",
+                "margin": "20922.10568",
+                "value": "258797.5",
+                "net": "+154186.9728",
+                "terms": "0.0100% per 4 hours",
+                "rollovertm": "1616672637",
+                "misc": "",
+                "oflags": ""
+            }
+        }"#;
+
+        let positions: HashMap<String, OpenPosition> =
+            serde_json::from_str(json).expect("Failed to deserialize");
+        let position = &positions["TF5GVO-T7ZZ2-6NBKBI"];
+        assert_eq!(position.status, "open");
+        assert!(matches!(position.r#type, TrateType::Buy));
+        assert_amount(&position.margin, "20922.10568");
+        assert_amount(position.net.as_ref().unwrap(), "154186.9728");
+        assert_eq!(position.rollover_time.as_deref(), Some("1616672637"));
+    }
 }
@@ -0,0 +1,188 @@
+//! Retry policy
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+
+type RetryablePredicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// Retry policy for the failed requests
+///
+/// The delay between the attempts grows exponentially, up to the max backoff.
+/// The private requests are signed again, with a new nonce, at every attempt.
+///
+/// Non-idempotent calls (i.e., order placement, withdrawals) are never retried, unless explicitly allowed:
+/// a timeout doesn't mean that the request hasn't been processed.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable: RetryablePredicate,
+    retry_non_idempotent: bool,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable: Arc::new(Error::is_retryable),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Construct a new retry policy, with the defaults:
+    /// 3 attempts, backoff from 500 ms to 10 secs, with jitter, retrying the errors matching [`Error::is_retryable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the max number of attempts (including the first one)
+    #[inline]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry
+    #[inline]
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the max delay between two attempts
+    #[inline]
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Randomize the delays, to avoid synchronized retries (default: `true`)
+    #[inline]
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set which errors are retryable (default: [`Error::is_retryable`])
+    #[inline]
+    pub fn retryable<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(predicate);
+        self
+    }
+
+    /// Retry also the non-idempotent calls (default: `false`)
+    #[inline]
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// Check if the failed attempt (starting from 1) must be retried.
+    pub(crate) fn should_retry(&self, error: &Error, attempt: u32, idempotent: bool) -> bool {
+        attempt < self.max_attempts
+            && (idempotent || self.retry_non_idempotent)
+            && (self.retryable)(error)
+    }
+
+    /// Delay after the failed attempt (starting from 1).
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exp: u32 = attempt.saturating_sub(1).min(31);
+        let backoff: Duration = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            // Between half and the full backoff
+            backoff.mul_f64(0.5 + random_fraction() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Random number in `[0, 1]`
+fn random_fraction() -> f64 {
+    let nanos: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(nanos);
+
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1))
+            .jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(100), Duration::from_secs(1));
+
+        let policy = policy.jitter(true);
+        for attempt in 1..10 {
+            let backoff = policy.backoff(attempt);
+            assert!(backoff <= Duration::from_secs(1));
+            assert!(backoff >= Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let error = Error::Kraken(vec!["EService:Unavailable".parse().unwrap()]);
+        let policy = RetryPolicy::new().max_attempts(3);
+
+        assert!(policy.should_retry(&error, 1, true));
+        assert!(policy.should_retry(&error, 2, true));
+        assert!(!policy.should_retry(&error, 3, true));
+
+        // Non-idempotent
+        assert!(!policy.should_retry(&error, 1, false));
+        let policy = policy.retry_non_idempotent(true);
+        assert!(policy.should_retry(&error, 1, false));
+
+        // Not retryable
+        assert!(!policy.should_retry(&Error::MissingResult, 1, true));
+        let policy = policy.retryable(|_| true);
+        assert!(policy.should_retry(&Error::MissingResult, 1, true));
+    }
+}
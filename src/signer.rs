@@ -0,0 +1,304 @@
+//! Request signers
+//!
+//! Kraken signs the private requests with `HMAC-SHA512(path + SHA256(nonce + post data))`, keyed with the
+//! base64-decoded API secret. The signer computes it, so the secret can live outside the process.
+
+use std::fmt;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use futures_util::future::{self, BoxFuture};
+use hmac::{Hmac, Mac};
+#[cfg(unix)]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+#[cfg(unix)]
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+#[cfg(unix)]
+use tokio::net::UnixStream;
+#[cfg(unix)]
+use tokio::time;
+use zeroize::Zeroizing;
+
+use crate::error::Error;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Default timeout of the signing service requests
+#[cfg(unix)]
+const DEFAULT_SIGNER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Max length of the signing service responses
+#[cfg(unix)]
+const MAX_SIGNER_RESPONSE_LEN: u64 = 4096;
+
+/// Signer of the private requests
+pub trait Signer: fmt::Debug + Send + Sync {
+    /// Sign the request, returning the base64 signature for the `API-Sign` header.
+    ///
+    /// * `path`: API path (i.e., `/0/private/Balance`)
+    /// * `nonce`: nonce of the request
+    /// * `body`: post data, including the nonce
+    fn sign<'a>(
+        &'a self,
+        path: &'a str,
+        nonce: u64,
+        body: &'a str,
+    ) -> BoxFuture<'a, Result<String, Error>>;
+}
+
+/// In-process HMAC-SHA512 signer, holding the API secret
+///
+/// The decoded secret is wiped from memory when dropped.
+#[derive(Clone)]
+pub struct HmacSigner {
+    secret: Zeroizing<Vec<u8>>,
+}
+
+impl fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSigner").finish()
+    }
+}
+
+impl HmacSigner {
+    /// Construct a new signer, with the base64 API secret
+    ///
+    /// Returns an error if the secret is empty or isn't valid base64.
+    pub fn new<S>(secret: S) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let encoded: Zeroizing<String> = Zeroizing::new(secret.into());
+        let secret: Zeroizing<Vec<u8>> = Zeroizing::new(STANDARD.decode(encoded.trim())?);
+
+        if secret.is_empty() {
+            return Err(Error::Signer(String::from("empty secret")));
+        }
+
+        Ok(Self { secret })
+    }
+
+    fn sign_sync(&self, path: &str, nonce: u64, body: &str) -> String {
+        let sha2_result = {
+            let mut hasher = Sha256::default();
+            hasher.update(nonce.to_string());
+            hasher.update(body);
+            hasher.finalize()
+        };
+
+        let mut mac =
+            HmacSha512::new_from_slice(&self.secret).expect("Hmac should work with any key length");
+        mac.update(path.as_bytes());
+        mac.update(&sha2_result);
+        let mac = mac.finalize().into_bytes();
+
+        STANDARD.encode(mac)
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign<'a>(
+        &'a self,
+        path: &'a str,
+        nonce: u64,
+        body: &'a str,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(future::ready(Ok(self.sign_sync(path, nonce, body))))
+    }
+}
+
+#[cfg(unix)]
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    path: &'a str,
+    nonce: u64,
+    body: &'a str,
+}
+
+#[cfg(unix)]
+#[derive(Deserialize)]
+struct SignResponse {
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Signer delegating to a local signing service, via Unix socket
+///
+/// For every request, a new connection is opened and a JSON line is sent:
+/// `{"path": "/0/private/Balance", "nonce": 1616492376594, "body": "{\"nonce\":1616492376594}"}`.
+///
+/// The service must reply with a JSON line: `{"signature": "<base64>"}` or `{"error": "<reason>"}`.
+/// The requests fail if the service doesn't reply within the timeout, or if the reply is longer than 4 KiB.
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+pub struct UnixSocketSigner {
+    path: PathBuf,
+    timeout: Duration,
+}
+
+#[cfg(unix)]
+impl UnixSocketSigner {
+    /// Construct a new signer, connecting to the socket at the path
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            timeout: DEFAULT_SIGNER_TIMEOUT,
+        }
+    }
+
+    /// Set the timeout of the requests, including the connection (default: 5 secs)
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    async fn request(&self, request: SignRequest<'_>) -> Result<String, Error> {
+        let response: String = time::timeout(self.timeout, self.exchange(request))
+            .await
+            .map_err(|_| Error::Signer(String::from("timed out")))??;
+
+        let response: SignResponse = serde_json::from_str(&response)?;
+
+        match (response.signature, response.error) {
+            (Some(signature), None) => Ok(signature),
+            (_, Some(error)) => Err(Error::Signer(error)),
+            (None, None) => Err(Error::Signer(String::from("missing signature"))),
+        }
+    }
+
+    /// Send the request line, and read the response line
+    async fn exchange(&self, request: SignRequest<'_>) -> Result<String, Error> {
+        let mut stream: UnixStream = UnixStream::connect(&self.path).await?;
+
+        let mut line: Vec<u8> = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        stream.write_all(&line).await?;
+
+        let mut response: String = String::new();
+        let len: usize = BufReader::new(stream.take(MAX_SIGNER_RESPONSE_LEN))
+            .read_line(&mut response)
+            .await?;
+
+        if len as u64 == MAX_SIGNER_RESPONSE_LEN && !response.ends_with('\n') {
+            return Err(Error::Signer(String::from("response too long")));
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(unix)]
+impl Signer for UnixSocketSigner {
+    fn sign<'a>(
+        &'a self,
+        path: &'a str,
+        nonce: u64,
+        body: &'a str,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(self.request(SignRequest { path, nonce, body }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/0/private/AddOrder";
+    const NONCE: u64 = 1616492376594;
+    const BODY: &str =
+        "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+    const SIGNATURE: &str =
+        "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ==";
+
+    #[tokio::test]
+    async fn test_hmac_signer() {
+        // Example from the Kraken docs
+        let signer = HmacSigner::new(
+            "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==",
+        )
+        .unwrap();
+        let sig = signer.sign(PATH, NONCE, BODY).await.unwrap();
+        assert_eq!(sig, SIGNATURE);
+    }
+
+    #[test]
+    fn test_hmac_signer_invalid_secret() {
+        assert!(HmacSigner::new("<secret>").is_err());
+        assert!(matches!(HmacSigner::new(""), Err(Error::Signer(..))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_signer() {
+        use tokio::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("kraken-signer-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        // Mock signing service
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).await.unwrap();
+
+            let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(request["path"], PATH);
+            assert_eq!(request["nonce"], NONCE);
+            assert_eq!(request["body"], BODY);
+
+            let response = format!("{{\"signature\": \"{SIGNATURE}\"}}\n");
+            writer.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let signer = UnixSocketSigner::new(&path);
+        let sig = signer.sign(PATH, NONCE, BODY).await.unwrap();
+        assert_eq!(sig, SIGNATURE);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_signer_misbehaving() {
+        use tokio::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!(
+            "kraken-signer-misbehaving-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        // Mock signing service: hangs at the first request, replies with an endless line at the second one
+        tokio::spawn(async move {
+            let (_hung, _) = listener.accept().await.unwrap();
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.write_all(&[b'a'; 8192]).await;
+            std::future::pending::<()>().await;
+        });
+
+        let signer = UnixSocketSigner::new(&path).timeout(Duration::from_millis(100));
+        match signer.sign(PATH, NONCE, BODY).await {
+            Err(Error::Signer(e)) => assert_eq!(e, "timed out"),
+            _ => panic!("Expected signer error"),
+        }
+        match signer.sign(PATH, NONCE, BODY).await {
+            Err(Error::Signer(e)) => assert_eq!(e, "response too long"),
+            _ => panic!("Expected signer error"),
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+//! Local order book
+
+use std::collections::BTreeMap;
+
+use super::message::{BookData, BookLevel, MessageType};
+use crate::amount::Amount;
+
+/// Price level key: the bits of the price (for positive floats, the order is the same as the numeric one)
+type PriceKey = u64;
+
+/// Local order book, built from the `book` channel snapshots and updates
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    depth: usize,
+    bids: BTreeMap<PriceKey, BookLevel>,
+    asks: BTreeMap<PriceKey, BookLevel>,
+}
+
+impl LocalOrderBook {
+    /// New empty order book, truncated at `depth` price levels per side
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+
+    /// Apply a snapshot or an update
+    pub fn apply(&mut self, kind: MessageType, data: &BookData) {
+        if kind == MessageType::Snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+
+        apply_levels(&mut self.bids, &data.bids);
+        apply_levels(&mut self.asks, &data.asks);
+
+        // Remove the levels out of scope: lowest bids and highest asks
+        while self.bids.len() > self.depth {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > self.depth {
+            self.asks.pop_last();
+        }
+    }
+
+    /// Bids, from the best (highest) price
+    pub fn bids(&self) -> impl Iterator<Item = BookLevel> + '_ {
+        self.bids.values().rev().cloned()
+    }
+
+    /// Asks, from the best (lowest) price
+    pub fn asks(&self) -> impl Iterator<Item = BookLevel> + '_ {
+        self.asks.values().cloned()
+    }
+
+    /// Compute the CRC32 checksum of the top 10 asks and bids
+    ///
+    /// <https://docs.kraken.com/api/docs/guides/spot-ws-book-v2>
+    pub fn checksum(&self, price_precision: usize, qty_precision: usize) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+
+        for level in self.asks().take(10).chain(self.bids().take(10)) {
+            hasher.update(checksum_part(&level.price, price_precision).as_bytes());
+            hasher.update(checksum_part(&level.qty, qty_precision).as_bytes());
+        }
+
+        hasher.finalize()
+    }
+}
+
+fn apply_levels(side: &mut BTreeMap<PriceKey, BookLevel>, levels: &[BookLevel]) {
+    for level in levels.iter() {
+        // Same key for the same price with different scales (i.e., `1.50` and `1.5`)
+        let key: PriceKey = level.price.to_f64().to_bits();
+
+        if level.qty.is_zero() {
+            side.remove(&key);
+        } else {
+            side.insert(key, level.clone());
+        }
+    }
+}
+
+/// Format the value with the precision, removing the decimal point and the leading zeros
+fn checksum_part(value: &Amount, precision: usize) -> String {
+    let value: String = format!("{:.precision$}", value.to_f64()).replace('.', "");
+    value.trim_start_matches('0').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::tests::amount;
+
+    fn level(price: &str, qty: &str) -> BookLevel {
+        BookLevel {
+            price: amount(price),
+            qty: amount(qty),
+        }
+    }
+
+    fn data(bids: Vec<BookLevel>, asks: Vec<BookLevel>) -> BookData {
+        BookData {
+            symbol: String::from("BTC/USD"),
+            bids,
+            asks,
+            checksum: 0,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_snapshot_and_update() {
+        let mut book = LocalOrderBook::new(2);
+        book.apply(
+            MessageType::Snapshot,
+            &data(
+                vec![level("45283.5", "0.1"), level("45283.4", "1.0")],
+                vec![level("45285.2", "0.001"), level("45286.4", "1.5")],
+            ),
+        );
+
+        // Remove a bid, insert a better ask (the worst one goes out of scope)
+        book.apply(
+            MessageType::Update,
+            &data(vec![level("45283.5", "0.0")], vec![level("45284.0", "2.0")]),
+        );
+
+        let bids: Vec<BookLevel> = book.bids().collect();
+        assert_eq!(bids, vec![level("45283.4", "1.0")]);
+        let asks: Vec<BookLevel> = book.asks().collect();
+        assert_eq!(
+            asks,
+            vec![level("45284.0", "2.0"), level("45285.2", "0.001")]
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut book = LocalOrderBook::new(10);
+        book.apply(
+            MessageType::Snapshot,
+            &data(
+                vec![level("45283.5", "0.1"), level("45283.4", "1.0")],
+                vec![level("45285.2", "0.001"), level("45286.4", "1.5")],
+            ),
+        );
+
+        assert_eq!(checksum_part(&amount("45285.2"), 1), "452852");
+        assert_eq!(checksum_part(&amount("0.001"), 8), "100000");
+
+        let expected: u32 =
+            crc32fast::hash(b"45285210000045286415000000045283510000000452834100000000");
+        assert_eq!(book.checksum(1, 8), expected);
+    }
+}
@@ -0,0 +1,838 @@
+//! WebSocket messages
+
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Value, json};
+
+use crate::amount::{
+    Amount, deserialize_optional_amount, serialize_amount_as_number,
+    serialize_optional_amount_as_number,
+};
+use crate::request::{OhlcInterval, OrderType, TimeInForce};
+use crate::response::TrateType;
+
+/// Channel subscription
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subscription {
+    /// Ticker (level 1)
+    Ticker {
+        /// Symbols (i.e., `BTC/USD`)
+        symbols: Vec<String>,
+    },
+    /// Order book (level 2)
+    Book {
+        /// Symbols (i.e., `BTC/USD`)
+        symbols: Vec<String>,
+        /// Number of price levels (10, 25, 100, 500 or 1000)
+        depth: u16,
+    },
+    /// Trades
+    Trade {
+        /// Symbols (i.e., `BTC/USD`)
+        symbols: Vec<String>,
+    },
+    /// OHLC candles
+    Ohlc {
+        /// Symbols (i.e., `BTC/USD`)
+        symbols: Vec<String>,
+        /// Candle interval
+        interval: OhlcInterval,
+    },
+    /// Reference data of assets and pairs
+    Instrument,
+    /// Order status and fills of the account (authenticated)
+    Executions,
+    /// Balances and ledger entries of the account (authenticated)
+    Balances,
+}
+
+impl Subscription {
+    /// Ticker subscription
+    pub fn ticker<I, S>(symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Ticker {
+            symbols: symbols.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+
+    /// Order book subscription
+    pub fn book<I, S>(symbols: I, depth: u16) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Book {
+            symbols: symbols.into_iter().map(|s| s.into()).collect(),
+            depth,
+        }
+    }
+
+    /// Trade subscription
+    pub fn trade<I, S>(symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Trade {
+            symbols: symbols.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+
+    /// OHLC subscription
+    pub fn ohlc<I, S>(symbols: I, interval: OhlcInterval) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Ohlc {
+            symbols: symbols.into_iter().map(|s| s.into()).collect(),
+            interval,
+        }
+    }
+
+    /// Channel name
+    pub fn channel(&self) -> &str {
+        match self {
+            Self::Ticker { .. } => "ticker",
+            Self::Book { .. } => "book",
+            Self::Trade { .. } => "trade",
+            Self::Ohlc { .. } => "ohlc",
+            Self::Instrument => "instrument",
+            Self::Executions => "executions",
+            Self::Balances => "balances",
+        }
+    }
+
+    /// Whether the channel requires an authenticated connection
+    pub fn is_private(&self) -> bool {
+        matches!(self, Self::Executions | Self::Balances)
+    }
+
+    /// Number of acknowledgements expected from the server (one per symbol)
+    pub(crate) fn expected_acks(&self) -> usize {
+        match self {
+            Self::Ticker { symbols }
+            | Self::Book { symbols, .. }
+            | Self::Trade { symbols }
+            | Self::Ohlc { symbols, .. } => symbols.len().max(1),
+            Self::Instrument | Self::Executions | Self::Balances => 1,
+        }
+    }
+
+    /// Subscribe/unsubscribe request params
+    pub(crate) fn params(&self) -> Value {
+        match self {
+            Self::Ticker { symbols } | Self::Trade { symbols } => json!({
+                "channel": self.channel(),
+                "symbol": symbols,
+            }),
+            Self::Book { symbols, depth } => json!({
+                "channel": self.channel(),
+                "symbol": symbols,
+                "depth": depth,
+            }),
+            Self::Ohlc { symbols, interval } => json!({
+                "channel": self.channel(),
+                "symbol": symbols,
+                "interval": interval.as_minutes(),
+            }),
+            Self::Instrument | Self::Executions | Self::Balances => json!({
+                "channel": self.channel(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WsRequest<'a> {
+    pub(crate) method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) params: Option<Value>,
+    pub(crate) req_id: u64,
+}
+
+/// Trigger of a conditional order
+#[derive(Debug, Clone, Serialize)]
+struct WsTrigger {
+    #[serde(serialize_with = "serialize_amount_as_number")]
+    price: Amount,
+}
+
+/// Add order request (WebSocket v2)
+#[derive(Debug, Clone, Serialize)]
+pub struct WsAddOrder {
+    order_type: OrderType,
+    side: TrateType,
+    #[serde(serialize_with = "serialize_amount_as_number")]
+    order_qty: Amount,
+    symbol: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_amount_as_number"
+    )]
+    limit_price: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    triggers: Option<WsTrigger>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_time_in_force"
+    )]
+    time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    post_only: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    reduce_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_userref: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    validate: bool,
+}
+
+impl WsAddOrder {
+    fn new<S>(order_type: OrderType, symbol: S, side: TrateType, qty: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            order_type,
+            side,
+            order_qty: qty,
+            symbol: symbol.into(),
+            limit_price: None,
+            triggers: None,
+            time_in_force: None,
+            post_only: false,
+            reduce_only: false,
+            order_userref: None,
+            cl_ord_id: None,
+            validate: false,
+        }
+    }
+
+    /// Market order
+    pub fn market<S>(symbol: S, side: TrateType, qty: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(OrderType::Market, symbol, side, qty)
+    }
+
+    /// Limit order
+    pub fn limit<S>(symbol: S, side: TrateType, qty: Amount, price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::Limit, symbol, side, qty);
+        order.limit_price = Some(price);
+        order
+    }
+
+    /// Stop loss order, triggered at `trigger_price`
+    pub fn stop_loss<S>(symbol: S, side: TrateType, qty: Amount, trigger_price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::StopLoss, symbol, side, qty);
+        order.triggers = Some(WsTrigger {
+            price: trigger_price,
+        });
+        order
+    }
+
+    /// Take profit order, triggered at `trigger_price`
+    pub fn take_profit<S>(symbol: S, side: TrateType, qty: Amount, trigger_price: Amount) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::TakeProfit, symbol, side, qty);
+        order.triggers = Some(WsTrigger {
+            price: trigger_price,
+        });
+        order
+    }
+
+    /// Stop loss limit order, placing a limit order at `limit_price` when `trigger_price` is reached
+    pub fn stop_loss_limit<S>(
+        symbol: S,
+        side: TrateType,
+        qty: Amount,
+        trigger_price: Amount,
+        limit_price: Amount,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::StopLossLimit, symbol, side, qty);
+        order.limit_price = Some(limit_price);
+        order.triggers = Some(WsTrigger {
+            price: trigger_price,
+        });
+        order
+    }
+
+    /// Take profit limit order, placing a limit order at `limit_price` when `trigger_price` is reached
+    pub fn take_profit_limit<S>(
+        symbol: S,
+        side: TrateType,
+        qty: Amount,
+        trigger_price: Amount,
+        limit_price: Amount,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut order = Self::new(OrderType::TakeProfitLimit, symbol, side, qty);
+        order.limit_price = Some(limit_price);
+        order.triggers = Some(WsTrigger {
+            price: trigger_price,
+        });
+        order
+    }
+
+    /// Set the time in force
+    #[inline]
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Post-only order (limit orders only)
+    #[inline]
+    pub fn post_only(mut self) -> Self {
+        self.post_only = true;
+        self
+    }
+
+    /// Reduce-only order (margin only)
+    #[inline]
+    pub fn reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    /// Set a user reference ID
+    #[inline]
+    pub fn userref(mut self, userref: i64) -> Self {
+        self.order_userref = Some(userref);
+        self
+    }
+
+    /// Set a client order ID
+    #[inline]
+    pub fn client_order_id<S>(mut self, id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.cl_ord_id = Some(id.into());
+        self
+    }
+
+    /// Validate the order without submitting it
+    #[inline]
+    pub fn validate(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+}
+
+fn serialize_time_in_force<S>(
+    time_in_force: &Option<TimeInForce>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match time_in_force {
+        Some(TimeInForce::Gtc) => serializer.serialize_str("gtc"),
+        Some(TimeInForce::Ioc) => serializer.serialize_str("ioc"),
+        Some(TimeInForce::Gtd) => serializer.serialize_str("gtd"),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Result of an order placed via WebSocket
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsAddOrderResult {
+    /// Order ID
+    pub order_id: String,
+    /// Client order ID
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+    /// User reference ID
+    #[serde(default)]
+    pub order_userref: Option<i64>,
+    /// Warnings
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Result of an order cancelled via WebSocket
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsCancelOrderResult {
+    /// Order ID
+    pub order_id: String,
+    /// Client order ID
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+}
+
+/// Response to a method request (i.e., `subscribe`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MethodResponse {
+    /// Method
+    pub method: String,
+    /// Whether the request was successful (not present for `pong`)
+    #[serde(default)]
+    pub success: Option<bool>,
+    /// Result
+    #[serde(default)]
+    pub result: Option<Value>,
+    /// Error message
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Request ID
+    #[serde(default)]
+    pub req_id: Option<u64>,
+}
+
+/// Message type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageType {
+    /// Snapshot
+    Snapshot,
+    /// Update
+    Update,
+}
+
+/// System status
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemStatus {
+    /// WebSocket API version
+    pub api_version: String,
+    /// Connection ID
+    pub connection_id: u64,
+    /// Trading engine status (i.e., `online`)
+    pub system: String,
+    /// WebSocket service version
+    pub version: String,
+}
+
+/// Ticker
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsTicker {
+    /// Symbol
+    pub symbol: String,
+    /// Best bid price
+    pub bid: Amount,
+    /// Best bid quantity
+    pub bid_qty: Amount,
+    /// Best ask price
+    pub ask: Amount,
+    /// Best ask quantity
+    pub ask_qty: Amount,
+    /// Last traded price
+    pub last: Amount,
+    /// 24h traded volume (base currency)
+    pub volume: Amount,
+    /// 24h volume weighted average price
+    pub vwap: Amount,
+    /// 24h lowest trade price
+    pub low: Amount,
+    /// 24h highest trade price
+    pub high: Amount,
+    /// 24h price change (quote currency)
+    pub change: Amount,
+    /// 24h price change (percentage points)
+    pub change_pct: Amount,
+}
+
+/// Order book price level
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookLevel {
+    /// Price
+    pub price: Amount,
+    /// Quantity (`0` to remove the level)
+    pub qty: Amount,
+}
+
+/// Order book snapshot or update
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookData {
+    /// Symbol
+    pub symbol: String,
+    /// Bids
+    #[serde(default)]
+    pub bids: Vec<BookLevel>,
+    /// Asks
+    #[serde(default)]
+    pub asks: Vec<BookLevel>,
+    /// CRC32 checksum of the top 10 bids and asks
+    pub checksum: u32,
+    /// RFC3339 timestamp of the book update
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Trade
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsTrade {
+    /// Symbol
+    pub symbol: String,
+    /// Side of the taker order
+    pub side: TrateType,
+    /// Price
+    pub price: Amount,
+    /// Quantity
+    pub qty: Amount,
+    /// Order type of the taker order (i.e., `limit`, `market`)
+    pub ord_type: String,
+    /// Trade ID
+    pub trade_id: u64,
+    /// RFC3339 timestamp of the trade
+    pub timestamp: String,
+}
+
+/// OHLC candle
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsCandle {
+    /// Symbol
+    pub symbol: String,
+    /// Open
+    pub open: Amount,
+    /// High
+    pub high: Amount,
+    /// Low
+    pub low: Amount,
+    /// Close
+    pub close: Amount,
+    /// Volume weighted average price
+    pub vwap: Amount,
+    /// Number of trades
+    pub trades: u64,
+    /// Volume
+    pub volume: Amount,
+    /// RFC3339 timestamp of the start of the interval
+    pub interval_begin: String,
+    /// Interval in minutes
+    pub interval: u32,
+    /// RFC3339 timestamp of the message
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Pair reference data
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsPair {
+    /// Symbol
+    pub symbol: String,
+    /// Base asset
+    pub base: String,
+    /// Quote asset
+    pub quote: String,
+    /// Number of decimal places of the prices
+    pub price_precision: usize,
+    /// Number of decimal places of the quantities
+    pub qty_precision: usize,
+}
+
+/// Instrument reference data
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstrumentData {
+    /// Pairs
+    #[serde(default)]
+    pub pairs: Vec<WsPair>,
+}
+
+/// Execution type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecType {
+    /// Order received, not yet validated
+    PendingNew,
+    /// Order created
+    New,
+    /// Order partially filled
+    Trade,
+    /// Order fully filled
+    Filled,
+    /// Iceberg order refilled
+    IcebergRefill,
+    /// Order cancelled
+    Canceled,
+    /// Order expired
+    Expired,
+    /// Order amended by the user
+    Amended,
+    /// Order amended by the engine
+    Restated,
+    /// Order status (snapshot)
+    Status,
+    /// Other
+    #[serde(other)]
+    Other,
+}
+
+/// Order execution
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsExecution {
+    /// Execution type
+    pub exec_type: ExecType,
+    /// Order ID
+    pub order_id: String,
+    /// Client order ID
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
+    /// User reference ID
+    #[serde(default)]
+    pub order_userref: Option<i64>,
+    /// Symbol
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Side
+    #[serde(default)]
+    pub side: Option<TrateType>,
+    /// Order type
+    #[serde(default)]
+    pub order_type: Option<OrderType>,
+    /// Order quantity
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub order_qty: Option<Amount>,
+    /// Limit price
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub limit_price: Option<Amount>,
+    /// Order status (i.e., `new`, `partially_filled`, `filled`)
+    #[serde(default)]
+    pub order_status: Option<String>,
+    /// Filled quantity
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub cum_qty: Option<Amount>,
+    /// Average fill price
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub avg_price: Option<Amount>,
+    /// Execution ID (trades only)
+    #[serde(default)]
+    pub exec_id: Option<String>,
+    /// Quantity of the trade (trades only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub last_qty: Option<Amount>,
+    /// Price of the trade (trades only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub last_price: Option<Amount>,
+    /// Fee paid, in USD
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub fee_usd_equiv: Option<Amount>,
+    /// RFC3339 timestamp of the execution
+    pub timestamp: String,
+}
+
+/// Asset balance, or balance change (update)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsBalance {
+    /// Asset
+    pub asset: String,
+    /// Asset class
+    #[serde(default)]
+    pub asset_class: Option<String>,
+    /// Total balance
+    pub balance: Amount,
+    /// Amount of the change (update only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub amount: Option<Amount>,
+    /// Fee paid (update only)
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub fee: Option<Amount>,
+    /// Ledger entry ID (update only)
+    #[serde(default)]
+    pub ledger_id: Option<String>,
+    /// Reference ID (update only)
+    #[serde(default)]
+    pub ref_id: Option<String>,
+    /// Ledger entry type (update only, i.e., `trade`, `deposit`)
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    /// RFC3339 timestamp of the change (update only)
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Channel message
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "channel", rename_all = "lowercase")]
+pub enum ChannelMessage {
+    /// Heartbeat, sent approximately once every second in the absence of other messages
+    Heartbeat,
+    /// System status
+    Status {
+        /// Status
+        data: Vec<SystemStatus>,
+    },
+    /// Ticker
+    Ticker {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Tickers
+        data: Vec<WsTicker>,
+    },
+    /// Order book
+    Book {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Books
+        data: Vec<BookData>,
+    },
+    /// Trades
+    Trade {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Trades
+        data: Vec<WsTrade>,
+    },
+    /// OHLC candles
+    Ohlc {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Candles
+        data: Vec<WsCandle>,
+    },
+    /// Instrument reference data
+    Instrument {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Reference data
+        data: InstrumentData,
+    },
+    /// Order executions
+    Executions {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Executions
+        data: Vec<WsExecution>,
+    },
+    /// Balances
+    Balances {
+        /// Message type
+        #[serde(rename = "type")]
+        kind: MessageType,
+        /// Balances
+        data: Vec<WsBalance>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum IncomingMessage {
+    Method(MethodResponse),
+    Channel(ChannelMessage),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::tests::{amount, assert_amount};
+
+    #[test]
+    fn test_subscription_params() {
+        let subscription = Subscription::book(["BTC/USD"], 10);
+        assert_eq!(
+            subscription.params(),
+            json!({"channel": "book", "symbol": ["BTC/USD"], "depth": 10})
+        );
+
+        let subscription = Subscription::ohlc(["BTC/USD", "ETH/USD"], OhlcInterval::OneHour);
+        assert_eq!(subscription.expected_acks(), 2);
+        assert_eq!(
+            subscription.params(),
+            json!({"channel": "ohlc", "symbol": ["BTC/USD", "ETH/USD"], "interval": 60})
+        );
+    }
+
+    #[test]
+    fn test_add_order_serialization() {
+        let order = WsAddOrder::stop_loss_limit(
+            "BTC/USD",
+            TrateType::Sell,
+            amount("0.5"),
+            amount("25000"),
+            amount("24900"),
+        )
+        .time_in_force(TimeInForce::Gtc)
+        .client_order_id("my-order");
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "order_type": "stop-loss-limit",
+                "side": "sell",
+                "order_qty": 0.5,
+                "symbol": "BTC/USD",
+                "limit_price": 24900.0,
+                "triggers": {"price": 25000.0},
+                "time_in_force": "gtc",
+                "cl_ord_id": "my-order",
+            })
+        );
+    }
+
+    #[test]
+    fn test_incoming_message_deserialization() {
+        let json = r#"{"channel": "heartbeat"}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            msg,
+            IncomingMessage::Channel(ChannelMessage::Heartbeat)
+        ));
+
+        let json = r#"{"method": "subscribe", "result": {"channel": "ticker", "snapshot": true, "symbol": "BTC/USD"}, "success": true, "time_in": "2023-09-25T09:04:31.742599Z", "time_out": "2023-09-25T09:04:31.742648Z", "req_id": 7}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            IncomingMessage::Method(response) => {
+                assert_eq!(response.method, "subscribe");
+                assert_eq!(response.success, Some(true));
+                assert_eq!(response.req_id, Some(7));
+            }
+            _ => panic!("Expected method response"),
+        }
+
+        let json = r#"{"channel": "ticker", "type": "snapshot", "data": [{"symbol": "BTC/USD", "bid": 26500.1, "bid_qty": 0.5, "ask": 26500.2, "ask_qty": 1.25, "last": 26500.1, "volume": 1234.5, "vwap": 26450.3, "low": 26100.0, "high": 26800.0, "change": 150.5, "change_pct": 0.57}]}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            IncomingMessage::Channel(ChannelMessage::Ticker { kind, data }) => {
+                assert_eq!(kind, MessageType::Snapshot);
+                assert_eq!(data[0].symbol, "BTC/USD");
+                assert_amount(&data[0].ask, "26500.2");
+            }
+            _ => panic!("Expected ticker message"),
+        }
+
+        let json = r#"{"channel": "trade", "type": "update", "data": [{"symbol": "BTC/USD", "side": "sell", "price": 26500.1, "qty": 0.01, "ord_type": "market", "trade_id": 4665906, "timestamp": "2023-09-25T07:49:37.708706Z"}]}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            IncomingMessage::Channel(ChannelMessage::Trade { data, .. }) => {
+                assert_eq!(data[0].side, TrateType::Sell);
+                assert_eq!(data[0].trade_id, 4665906);
+            }
+            _ => panic!("Expected trade message"),
+        }
+
+        let json = r#"{"channel": "executions", "type": "update", "data": [{"order_id": "OK4GJX-KSTLS-7DZZO5", "order_userref": 3, "exec_id": "TT4WGO-BHQKD-OPDBNG", "exec_type": "trade", "symbol": "BTC/USD", "side": "buy", "order_type": "limit", "last_qty": 0.1, "last_price": 26500.1, "cum_qty": 0.1, "avg_price": 26500.1, "order_status": "filled", "fee_usd_equiv": 6.89, "timestamp": "2023-09-22T10:33:05.709993Z"}], "sequence": 4}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            IncomingMessage::Channel(ChannelMessage::Executions { data, .. }) => {
+                assert_eq!(data[0].exec_type, ExecType::Trade);
+                assert_eq!(data[0].order_type, Some(OrderType::Limit));
+                assert_amount(data[0].last_qty.as_ref().unwrap(), "0.1");
+            }
+            _ => panic!("Expected executions message"),
+        }
+    }
+}
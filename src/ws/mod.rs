@@ -0,0 +1,812 @@
+//! Kraken WebSocket v2 client
+//!
+//! WebSocket v2 sends prices and quantities as JSON numbers: they are parsed as [`Amount`](crate::amount::Amount),
+//! like the REST amounts.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::{self, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+pub mod book;
+pub mod message;
+
+use self::book::LocalOrderBook;
+use self::message::{
+    BookData, ChannelMessage, IncomingMessage, MessageType, MethodResponse, Subscription,
+    WsAddOrder, WsAddOrderResult, WsCancelOrderResult, WsRequest,
+};
+use crate::client::KrakenClient;
+use crate::constant::{WS_AUTH_URL, WS_PUBLIC_URL};
+use crate::error::Error;
+use crate::response::WebSocketsToken;
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type WsStream = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// Interval between the pings sent to the server
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// Max time without receiving any message, before considering the connection dead
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Initial delay before reconnecting
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Max delay before reconnecting
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// Capacity of the notifications channel
+const NOTIFICATIONS_CAPACITY: usize = 4096;
+/// Margin before the token expiration, after which a new token is requested
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// WebSocket notification
+#[derive(Debug, Clone)]
+pub enum WsNotification {
+    /// Connection established (the subscriptions are restored automatically)
+    Connected,
+    /// Connection lost (a reconnection is attempted automatically)
+    Disconnected,
+    /// Channel message
+    Message(ChannelMessage),
+    /// The checksum of the local order book doesn't match: the book is re-subscribed to get a new snapshot
+    ChecksumMismatch {
+        /// Symbol
+        symbol: String,
+    },
+    /// Message that can't be parsed
+    ///
+    /// The request it responds to fails, and the order books it's meant for are re-subscribed.
+    Unparsed {
+        /// Raw message
+        text: String,
+        /// Parse error
+        error: String,
+    },
+}
+
+enum Command {
+    Subscribe {
+        subscription: Subscription,
+        reply: oneshot::Sender<Result<Vec<MethodResponse>, Error>>,
+    },
+    Unsubscribe {
+        subscription: Subscription,
+        reply: oneshot::Sender<Result<Vec<MethodResponse>, Error>>,
+    },
+    /// Authenticated request (i.e., `add_order`)
+    Request {
+        method: &'static str,
+        params: Value,
+        reply: oneshot::Sender<Result<Vec<MethodResponse>, Error>>,
+    },
+}
+
+/// Kraken WebSocket v2 client
+///
+/// The connection is handled by a background task, which reconnects automatically and restores the subscriptions.
+/// The task stops when all the clones of the client are dropped.
+///
+/// The authenticated client can also subscribe to the private channels and manage orders.
+/// The token needed for this is requested via REST, and refreshed at every reconnection.
+#[derive(Debug, Clone)]
+pub struct KrakenWsClient {
+    commands: mpsc::UnboundedSender<Command>,
+    notifications: broadcast::Sender<WsNotification>,
+}
+
+impl KrakenWsClient {
+    /// Construct a new client for the public WebSocket API.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self::with_url(Url::parse(WS_PUBLIC_URL)?))
+    }
+
+    /// Construct a new client for a custom WebSocket URL.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn with_url(url: Url) -> Self {
+        Self::spawn(url, None)
+    }
+
+    /// Construct a new authenticated client, getting the tokens with the API keys of the REST client.
+    ///
+    /// Must be called within a tokio runtime.
+    pub async fn authenticated(client: KrakenClient) -> Result<Self, Error> {
+        Self::authenticated_with_url(client, Url::parse(WS_AUTH_URL)?).await
+    }
+
+    /// Construct a new authenticated client for a custom WebSocket URL.
+    ///
+    /// Must be called within a tokio runtime.
+    pub async fn authenticated_with_url(client: KrakenClient, url: Url) -> Result<Self, Error> {
+        let mut tokens: TokenProvider = TokenProvider {
+            client,
+            token: None,
+        };
+
+        // Fail early if the credentials are missing or invalid
+        tokens.token().await?;
+
+        Ok(Self::spawn(url, Some(tokens)))
+    }
+
+    fn spawn(url: Url, tokens: Option<TokenProvider>) -> Self {
+        let (commands, commands_rx) = mpsc::unbounded_channel();
+        let (notifications, _) = broadcast::channel(NOTIFICATIONS_CAPACITY);
+
+        let task: WsTask = WsTask {
+            url,
+            commands: commands_rx,
+            notifications: notifications.clone(),
+            req_id: 0,
+            tokens,
+            subscriptions: Vec::new(),
+            pending: HashMap::new(),
+            books: HashMap::new(),
+            precisions: HashMap::new(),
+        };
+        tokio::spawn(task.run());
+
+        Self {
+            commands,
+            notifications,
+        }
+    }
+
+    /// Get a receiver of the notifications
+    pub fn notifications(&self) -> broadcast::Receiver<WsNotification> {
+        self.notifications.subscribe()
+    }
+
+    /// Subscribe to a channel
+    ///
+    /// Returns an error if the subscription is rejected for at least one symbol.
+    pub async fn subscribe(&self, subscription: Subscription) -> Result<(), Error> {
+        let (reply, rx) = oneshot::channel();
+        self.send_command(Command::Subscribe {
+            subscription,
+            reply,
+        })?;
+        check_responses(&rx.await.map_err(|_| Error::WebSocketDisconnected)??)
+    }
+
+    /// Unsubscribe from a channel
+    pub async fn unsubscribe(&self, subscription: Subscription) -> Result<(), Error> {
+        let (reply, rx) = oneshot::channel();
+        self.send_command(Command::Unsubscribe {
+            subscription,
+            reply,
+        })?;
+        check_responses(&rx.await.map_err(|_| Error::WebSocketDisconnected)??)
+    }
+
+    /// Place a new order (authenticated client only)
+    ///
+    /// If the connection is lost before the response arrives, [`Error::WebSocketDisconnected`] is returned:
+    /// the order may have been placed anyway.
+    pub async fn add_order(&self, order: &WsAddOrder) -> Result<WsAddOrderResult, Error> {
+        self.request("add_order", serde_json::to_value(order)?)
+            .await
+    }
+
+    /// Cancel an open order by order ID (authenticated client only)
+    pub async fn cancel_order(&self, order_id: &str) -> Result<WsCancelOrderResult, Error> {
+        self.request(
+            "cancel_order",
+            serde_json::json!({ "order_id": [order_id] }),
+        )
+        .await
+    }
+
+    async fn request<T>(&self, method: &'static str, params: Value) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let (reply, rx) = oneshot::channel();
+        self.send_command(Command::Request {
+            method,
+            params,
+            reply,
+        })?;
+
+        let responses: Vec<MethodResponse> =
+            rx.await.map_err(|_| Error::WebSocketDisconnected)??;
+        check_responses(&responses)?;
+
+        let result: Value = responses
+            .into_iter()
+            .find_map(|r| r.result)
+            .ok_or(Error::MissingResult)?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    fn send_command(&self, command: Command) -> Result<(), Error> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::WebSocketDisconnected)
+    }
+}
+
+fn check_responses(responses: &[MethodResponse]) -> Result<(), Error> {
+    for response in responses.iter() {
+        if response.success == Some(false) {
+            return Err(Error::WebSocketRequest(
+                response.error.clone().unwrap_or_default(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Provider of the tokens for the authenticated connections
+struct TokenProvider {
+    client: KrakenClient,
+    /// Current token, with its expiration
+    token: Option<(String, Instant)>,
+}
+
+impl TokenProvider {
+    /// Get the current token, requesting a new one if expired
+    async fn token(&mut self) -> Result<String, Error> {
+        if let Some((token, expiration)) = &self.token {
+            if Instant::now() + TOKEN_REFRESH_MARGIN < *expiration {
+                return Ok(token.clone());
+            }
+        }
+
+        let token: WebSocketsToken = self.client.websockets_token().await?;
+        let expiration: Instant = Instant::now() + Duration::from_secs(token.expires);
+        self.token = Some((token.token.clone(), expiration));
+
+        Ok(token.token)
+    }
+}
+
+/// Request waiting for the server responses
+struct Pending {
+    /// Number of responses still expected
+    remaining: usize,
+    responses: Vec<MethodResponse>,
+    reply: oneshot::Sender<Result<Vec<MethodResponse>, Error>>,
+    /// Subscription to forget if the request fails
+    subscription: Option<Subscription>,
+}
+
+/// Why the connection loop ended
+enum LoopExit {
+    /// Connection lost: reconnect
+    Reconnect,
+    /// All the clients have been dropped: stop the task
+    Shutdown,
+}
+
+/// State of a local order book
+enum BookState {
+    /// Waiting for the snapshot (new subscription or checksum mismatch): the updates are dropped
+    AwaitingSnapshot,
+    /// Built from the snapshot and the following updates
+    Synced(LocalOrderBook),
+}
+
+struct WsTask {
+    url: Url,
+    commands: mpsc::UnboundedReceiver<Command>,
+    notifications: broadcast::Sender<WsNotification>,
+    req_id: u64,
+    /// Tokens for the private channels and methods (authenticated client only)
+    tokens: Option<TokenProvider>,
+    /// Active subscriptions, restored at every reconnection
+    subscriptions: Vec<Subscription>,
+    pending: HashMap<u64, Pending>,
+    /// Local order books, by symbol
+    books: HashMap<String, BookState>,
+    /// Price and quantity precisions, by symbol (from the `instrument` channel)
+    precisions: HashMap<String, (usize, usize)>,
+}
+
+impl WsTask {
+    async fn run(mut self) {
+        let mut delay: Duration = MIN_RECONNECT_DELAY;
+
+        loop {
+            match tokio_tungstenite::connect_async(self.url.as_str()).await {
+                Ok((stream, _)) => {
+                    delay = MIN_RECONNECT_DELAY;
+
+                    // A new token for every connection
+                    if let Some(tokens) = &mut self.tokens {
+                        tokens.token = None;
+                    }
+
+                    let (mut sink, mut stream) = stream.split();
+                    let _ = self.notifications.send(WsNotification::Connected);
+
+                    let exit: LoopExit = self.connection_loop(&mut sink, &mut stream).await;
+
+                    // Fail the pending requests: the responses will never arrive
+                    for (_, pending) in self.pending.drain() {
+                        let _ = pending.reply.send(Err(Error::WebSocketDisconnected));
+                    }
+                    self.books.clear();
+
+                    let _ = self.notifications.send(WsNotification::Disconnected);
+
+                    if let LoopExit::Shutdown = exit {
+                        let _ = sink.close().await;
+                        return;
+                    }
+                }
+                Err(..) => {
+                    // Drain the commands received while disconnected
+                    while let Ok(command) = self.commands.try_recv() {
+                        self.reject(command);
+                    }
+
+                    if self.commands.is_closed() {
+                        return;
+                    }
+                }
+            }
+
+            time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    async fn connection_loop(&mut self, sink: &mut WsSink, stream: &mut WsStream) -> LoopExit {
+        // Restore the subscriptions
+        for subscription in self.subscriptions.clone().iter() {
+            if self
+                .send_subscription("subscribe", subscription, sink)
+                .await
+                .is_err()
+            {
+                return LoopExit::Reconnect;
+            }
+        }
+
+        let mut ping = time::interval(PING_INTERVAL);
+        let mut last_message: Instant = Instant::now();
+
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => match command {
+                    Some(command) => {
+                        if self.handle_command(command, sink).await.is_err() {
+                            return LoopExit::Reconnect;
+                        }
+                    }
+                    None => return LoopExit::Shutdown,
+                },
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        last_message = Instant::now();
+                        if self.handle_text(text.as_str(), sink).await.is_err() {
+                            return LoopExit::Reconnect;
+                        }
+                    }
+                    Some(Ok(Message::Close(..))) | Some(Err(..)) | None => return LoopExit::Reconnect,
+                    Some(Ok(..)) => last_message = Instant::now(),
+                },
+                _ = ping.tick() => {
+                    if last_message.elapsed() > IDLE_TIMEOUT {
+                        return LoopExit::Reconnect;
+                    }
+
+                    if self.send_request("ping", None, sink).await.is_err() {
+                        return LoopExit::Reconnect;
+                    }
+                }
+            }
+        }
+    }
+
+    fn next_req_id(&mut self) -> u64 {
+        self.req_id += 1;
+        self.req_id
+    }
+
+    fn reject(&self, command: Command) {
+        match command {
+            Command::Subscribe { reply, .. }
+            | Command::Unsubscribe { reply, .. }
+            | Command::Request { reply, .. } => {
+                let _ = reply.send(Err(Error::WebSocketDisconnected));
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, command: Command, sink: &mut WsSink) -> Result<(), Error> {
+        match command {
+            Command::Subscribe {
+                subscription,
+                reply,
+            } => {
+                let params: Value = match self.subscription_params(&subscription).await {
+                    Ok(params) => params,
+                    Err(e) => {
+                        let _ = reply.send(Err(e));
+                        return Ok(());
+                    }
+                };
+
+                // The book checksums can be verified only knowing the precisions
+                if matches!(subscription, Subscription::Book { .. })
+                    && !self.subscriptions.contains(&Subscription::Instrument)
+                {
+                    self.subscriptions.push(Subscription::Instrument);
+                    self.send_subscription("subscribe", &Subscription::Instrument, sink)
+                        .await?;
+                }
+
+                if !self.subscriptions.contains(&subscription) {
+                    self.subscriptions.push(subscription.clone());
+                }
+
+                let req_id: u64 = self.send_request("subscribe", Some(params), sink).await?;
+                self.pending.insert(
+                    req_id,
+                    Pending {
+                        remaining: subscription.expected_acks(),
+                        responses: Vec::new(),
+                        reply,
+                        subscription: Some(subscription),
+                    },
+                );
+            }
+            Command::Unsubscribe {
+                subscription,
+                reply,
+            } => {
+                let params: Value = match self.subscription_params(&subscription).await {
+                    Ok(params) => params,
+                    Err(e) => {
+                        let _ = reply.send(Err(e));
+                        return Ok(());
+                    }
+                };
+
+                self.subscriptions.retain(|s| s != &subscription);
+
+                let req_id: u64 = self.send_request("unsubscribe", Some(params), sink).await?;
+                self.pending.insert(
+                    req_id,
+                    Pending {
+                        remaining: subscription.expected_acks(),
+                        responses: Vec::new(),
+                        reply,
+                        subscription: None,
+                    },
+                );
+            }
+            Command::Request {
+                method,
+                mut params,
+                reply,
+            } => {
+                match self.token().await {
+                    Ok(token) => params["token"] = Value::String(token),
+                    Err(e) => {
+                        let _ = reply.send(Err(e));
+                        return Ok(());
+                    }
+                }
+
+                let req_id: u64 = self.send_request(method, Some(params), sink).await?;
+                self.pending.insert(
+                    req_id,
+                    Pending {
+                        remaining: 1,
+                        responses: Vec::new(),
+                        reply,
+                        subscription: None,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_text(&mut self, text: &str, sink: &mut WsSink) -> Result<(), Error> {
+        let message: IncomingMessage = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => {
+                for symbol in self.handle_unparsed(text, e).iter() {
+                    self.resubscribe_book(symbol, sink).await?;
+                }
+                return Ok(());
+            }
+        };
+
+        match message {
+            IncomingMessage::Method(response) => self.handle_response(response),
+            IncomingMessage::Channel(message) => {
+                match &message {
+                    ChannelMessage::Instrument { data, .. } => {
+                        for pair in data.pairs.iter() {
+                            self.precisions.insert(
+                                pair.symbol.clone(),
+                                (pair.price_precision, pair.qty_precision),
+                            );
+                        }
+                    }
+                    ChannelMessage::Book { kind, data } => {
+                        for book in data.iter() {
+                            if !self.verify_book(*kind, book) {
+                                let _ = self.notifications.send(WsNotification::ChecksumMismatch {
+                                    symbol: book.symbol.clone(),
+                                });
+                                self.resubscribe_book(&book.symbol, sink).await?;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+
+                let _ = self.notifications.send(WsNotification::Message(message));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify a message that can't be parsed, and fail the request it responds to (if any).
+    ///
+    /// Returns the symbols of the subscribed books it's meant for: without it, the local books diverge.
+    fn handle_unparsed(&mut self, text: &str, error: serde_json::Error) -> Vec<String> {
+        let _ = self.notifications.send(WsNotification::Unparsed {
+            text: text.to_string(),
+            error: error.to_string(),
+        });
+
+        let Ok(value) = serde_json::from_str::<Value>(text) else {
+            return Vec::new();
+        };
+
+        if let Some(pending) = value["req_id"]
+            .as_u64()
+            .and_then(|req_id| self.pending.remove(&req_id))
+        {
+            let _ = pending.reply.send(Err(Error::Json(error)));
+            return Vec::new();
+        }
+
+        if value["channel"] != "book" {
+            return Vec::new();
+        }
+
+        value["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|book| book["symbol"].as_str())
+            .filter(|symbol| self.book_depth(symbol).is_some())
+            .map(String::from)
+            .collect()
+    }
+
+    fn handle_response(&mut self, response: MethodResponse) {
+        let Some(req_id) = response.req_id else {
+            return;
+        };
+
+        let Some(pending) = self.pending.get_mut(&req_id) else {
+            return;
+        };
+
+        pending.remaining = pending.remaining.saturating_sub(1);
+        pending.responses.push(response);
+
+        if pending.remaining == 0 {
+            if let Some(pending) = self.pending.remove(&req_id) {
+                // Forget the subscription if it has been rejected
+                if let Some(subscription) = &pending.subscription {
+                    if pending.responses.iter().any(|r| r.success == Some(false)) {
+                        self.subscriptions.retain(|s| s != subscription);
+                    }
+                }
+
+                let _ = pending.reply.send(Ok(pending.responses));
+            }
+        }
+    }
+
+    /// Apply the book message to the local book and verify the checksum, if the precisions are known.
+    ///
+    /// The updates received while waiting for the snapshot are ignored.
+    fn verify_book(&mut self, kind: MessageType, data: &BookData) -> bool {
+        let depth: usize = self.book_depth(&data.symbol).unwrap_or(10) as usize;
+
+        let state: &mut BookState = self
+            .books
+            .entry(data.symbol.clone())
+            .or_insert(BookState::AwaitingSnapshot);
+
+        if kind == MessageType::Snapshot {
+            *state = BookState::Synced(LocalOrderBook::new(depth));
+        }
+
+        let BookState::Synced(book) = state else {
+            return true;
+        };
+        book.apply(kind, data);
+
+        match self.precisions.get(&data.symbol) {
+            Some((price_precision, qty_precision)) => {
+                book.checksum(*price_precision, *qty_precision) == data.checksum
+            }
+            None => true,
+        }
+    }
+
+    /// Depth of the book subscription of the symbol, if subscribed
+    fn book_depth(&self, symbol: &str) -> Option<u16> {
+        self.subscriptions.iter().find_map(|s| match s {
+            Subscription::Book { symbols, depth } if symbols.iter().any(|s| s == symbol) => {
+                Some(*depth)
+            }
+            _ => None,
+        })
+    }
+
+    async fn resubscribe_book(&mut self, symbol: &str, sink: &mut WsSink) -> Result<(), Error> {
+        // Drop the updates still in flight, until the snapshot of the new subscription
+        self.books
+            .insert(symbol.to_string(), BookState::AwaitingSnapshot);
+
+        let depth: u16 = self.book_depth(symbol).unwrap_or(10);
+
+        let subscription: Subscription = Subscription::book([symbol], depth);
+        self.send_subscription("unsubscribe", &subscription, sink)
+            .await?;
+        self.send_subscription("subscribe", &subscription, sink)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn token(&mut self) -> Result<String, Error> {
+        match &mut self.tokens {
+            Some(tokens) => tokens.token().await,
+            None => Err(Error::MissingCredentials),
+        }
+    }
+
+    /// Subscription params, with the token for the private channels
+    async fn subscription_params(&mut self, subscription: &Subscription) -> Result<Value, Error> {
+        let mut params: Value = subscription.params();
+
+        if subscription.is_private() {
+            params["token"] = Value::String(self.token().await?);
+        }
+
+        Ok(params)
+    }
+
+    async fn send_subscription(
+        &mut self,
+        method: &str,
+        subscription: &Subscription,
+        sink: &mut WsSink,
+    ) -> Result<u64, Error> {
+        let params: Value = self.subscription_params(subscription).await?;
+        self.send_request(method, Some(params), sink).await
+    }
+
+    async fn send_request(
+        &mut self,
+        method: &str,
+        params: Option<Value>,
+        sink: &mut WsSink,
+    ) -> Result<u64, Error> {
+        let req_id: u64 = self.next_req_id();
+        let request: WsRequest = WsRequest {
+            method,
+            params,
+            req_id,
+        };
+        let json: String = serde_json::to_string(&request)?;
+        sink.send(Message::Text(json.into())).await?;
+        Ok(req_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> WsTask {
+        let (_, commands) = mpsc::unbounded_channel();
+        let (notifications, _) = broadcast::channel(NOTIFICATIONS_CAPACITY);
+
+        WsTask {
+            url: Url::parse(WS_PUBLIC_URL).unwrap(),
+            commands,
+            notifications,
+            req_id: 0,
+            tokens: None,
+            subscriptions: vec![Subscription::book(["BTC/USD"], 10)],
+            pending: HashMap::new(),
+            books: HashMap::new(),
+            precisions: HashMap::from([(String::from("BTC/USD"), (1, 8))]),
+        }
+    }
+
+    fn book(bids: &str, checksum: u32) -> BookData {
+        let json = format!(
+            r#"{{"symbol": "BTC/USD", "bids": {bids}, "asks": [], "checksum": {checksum}}}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_verify_book_awaiting_snapshot() {
+        let mut task = task();
+
+        // Updates before the snapshot are dropped, not verified
+        let update = book(r#"[{"price": 45283.5, "qty": 0.1}]"#, 1);
+        assert!(task.verify_book(MessageType::Update, &update));
+        assert!(matches!(
+            task.books.get("BTC/USD"),
+            Some(BookState::AwaitingSnapshot)
+        ));
+
+        let snapshot = book(
+            r#"[{"price": 45283.5, "qty": 0.1}]"#,
+            crc32fast::hash(b"45283510000000"),
+        );
+        assert!(task.verify_book(MessageType::Snapshot, &snapshot));
+        assert!(matches!(
+            task.books.get("BTC/USD"),
+            Some(BookState::Synced(..))
+        ));
+
+        // Mismatch on a synced book
+        assert!(!task.verify_book(MessageType::Update, &update));
+    }
+
+    #[test]
+    fn test_handle_unparsed() {
+        let mut task = task();
+        let mut notifications = task.notifications.subscribe();
+
+        // Book update: the subscribed books must be resynced
+        let text = r#"{"channel": "book", "type": "update", "data": [{"symbol": "BTC/USD", "bids": "invalid"}, {"symbol": "ETH/USD", "bids": "invalid"}]}"#;
+        let error = serde_json::from_str::<IncomingMessage>(text).unwrap_err();
+        assert_eq!(
+            task.handle_unparsed(text, error),
+            vec![String::from("BTC/USD")]
+        );
+        assert!(matches!(
+            notifications.try_recv(),
+            Ok(WsNotification::Unparsed { text: t, .. }) if t == text
+        ));
+
+        // Response to a pending request: the request fails
+        let (reply, mut rx) = oneshot::channel();
+        task.pending.insert(
+            3,
+            Pending {
+                remaining: 1,
+                responses: Vec::new(),
+                reply,
+                subscription: None,
+            },
+        );
+        let text = r#"{"req_id": 3, "success": "invalid"}"#;
+        let error = serde_json::from_str::<IncomingMessage>(text).unwrap_err();
+        assert!(task.handle_unparsed(text, error).is_empty());
+        assert!(task.pending.is_empty());
+        assert!(matches!(rx.try_recv(), Ok(Err(Error::Json(..)))));
+    }
+}